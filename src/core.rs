@@ -1,9 +1,52 @@
 //! A module with the core UI elements - Application and Window.
 
 mod application;
+mod clipboard;
+mod cursor;
+mod diagnostics;
+mod dialog;
+mod dispatch;
+mod drag;
+mod draw_context;
+mod executor;
+mod file_drop;
+mod frame_stats;
+mod gesture;
+mod harness;
+mod host;
+mod hot_reload;
+mod input;
+mod monitor;
+mod pixels;
+mod recording;
+mod scheduler;
+mod session;
+mod snapshot;
+mod view;
 mod window;
+mod window_change;
 
 use glutin::prelude::*;
 use window::RawWindow;
 
-pub use application::Application;
+pub use application::{Application, ApplicationProxy};
+pub use clipboard::{
+    Clipboard, ClipboardContent, ClipboardKind, ClipboardProvider, MemoryClipboard, SystemClipboard,
+};
+pub use cursor::{Cursor, CustomCursor};
+pub use dialog::{Dialog, DialogHandle, DialogKind, DialogResponse};
+pub use drag::DragDrop;
+pub use draw_context::DrawContext;
+pub use executor::Spawner;
+pub use file_drop::FileDrop;
+pub use frame_stats::FrameStats;
+pub use gesture::{Gesture, GesturePhase, TouchPoint};
+pub use harness::Harness;
+pub use input::Input;
+pub use monitor::Monitor;
+pub use recording::{RecordedEvent, Recording, RecordingError};
+pub use scheduler::{Debounced, Scheduler, TimerId};
+pub use session::SessionStore;
+pub use snapshot::{Snapshot, SnapshotError, UPDATE_GOLDENS_VAR};
+pub use window::Window;
+pub use window_change::WindowChange;
@@ -1,16 +1,23 @@
+use crate::core::dispatch::{Dispatcher, Platform};
+use crate::core::executor::Executor;
+use crate::core::hot_reload::HotReloader;
+use crate::core::recording::{Recorder, Replay};
 use crate::core::window::Window;
 use crate::core::*;
-use crate::renderer;
+use crate::ui::Store;
 
 use glutin::config::{Config, ConfigTemplateBuilder};
 use glutin_winit::DisplayBuilder;
-use std::collections::HashMap;
+use std::path::Path;
 use std::process;
+use std::time::Instant;
 use winit::application::ApplicationHandler;
 use winit::error::EventLoopError;
-use winit::event::{ElementState, KeyEvent, Modifiers, StartCause, WindowEvent};
-use winit::event_loop::{ActiveEventLoop, EventLoop};
-use winit::window::WindowId;
+use winit::event::{DeviceEvent, DeviceId, StartCause, WindowEvent};
+use winit::event_loop::{
+    ActiveEventLoop, ControlFlow, DeviceEvents, EventLoop, EventLoopClosed, EventLoopProxy,
+};
+use winit::window::{WindowAttributes, WindowId};
 
 /// A module with known application exit codes.
 mod exit_codes {
@@ -23,24 +30,79 @@ mod exit_codes {
 }
 
 /// An application, the main entrypoint of the program.
-pub struct Application {
-    event_loop: EventLoop<()>,
-    application: ApplicationInternal,
+///
+/// `T` is the type of messages that can be sent to the application from other threads
+/// through an [`ApplicationProxy`].
+pub struct Application<T: 'static = ()> {
+    event_loop: EventLoop<AppEvent<T>>,
+    application: ApplicationInternal<T>,
     initial_raw_window: RawWindow,
 }
 
+/// A cloneable handle for sending messages to an [`Application`] from any thread.
+///
+/// Every message wakes up the event loop and is passed to the application's
+/// [message handler](Application::with_message_handler).
+pub struct ApplicationProxy<T: 'static> {
+    proxy: EventLoopProxy<AppEvent<T>>,
+}
+
+/// A handler of the messages sent through an [`ApplicationProxy`].
+type MessageHandler<T> = Box<dyn FnMut(T, &Store)>;
+
+/// An event sent to the event loop, either by the user or by the application itself.
+enum AppEvent<T> {
+    /// A message sent through an [`ApplicationProxy`].
+    Message(T),
+    /// A file watched by the [`HotReloader`] has changed.
+    FilesChanged,
+    /// A task spawned on the [`Executor`] has been woken.
+    TaskWoken,
+}
+
 /// An internal struct handling OS event when the application is run.
-struct ApplicationInternal {
+///
+/// The inputs of the windows are dispatched by the [`Dispatcher`], like in a [`Harness`].
+struct ApplicationInternal<T> {
     gl_config: Config,
-    window_indices: HashMap<WindowId, usize>, // Normally if the EventLoop.ControlFlow is not Poll,
-    windows: Vec<Window>,                     // there should just be a HashSet<WindowId, Window>
-    keyboard_modifiers: Modifiers,
+    dispatcher: Dispatcher,
+    message_handler: Option<MessageHandler<T>>,
+    recorder: Option<Recorder>,
+    replay: Option<Replay>,
+    listening_device_events: bool,
+}
+
+/// The event loop the [`Dispatcher`] creates windows with while the application is running.
+struct EventLoopPlatform<'a> {
+    event_loop: &'a ActiveEventLoop,
+    gl_config: &'a Config,
 }
 
-impl Application {
+impl<'a> EventLoopPlatform<'a> {
+    fn new(event_loop: &'a ActiveEventLoop, gl_config: &'a Config) -> Self {
+        EventLoopPlatform {
+            event_loop,
+            gl_config,
+        }
+    }
+}
+
+impl Platform for EventLoopPlatform<'_> {
+    fn create_window(&mut self, attrs: WindowAttributes) -> Window {
+        Window::with_attrs(attrs, self.event_loop, self.gl_config)
+    }
+
+    fn exit(&mut self) {
+        self.event_loop.exit();
+    }
+}
+
+impl<T: Send + 'static> Application<T> {
     /// Creates a new application.
     pub fn new() -> Self {
-        let event_loop = EventLoop::new().expect("Failed to create event loop");
+        let event_loop = EventLoop::with_user_event()
+            .build()
+            .expect("Failed to create event loop");
 
         let template = ConfigTemplateBuilder::new().with_transparency(true);
 
@@ -63,20 +125,167 @@ impl Application {
             .expect("Could not create OpenGL config");
         let raw_window = raw_window.expect("Could not create window with OpenGL context");
 
-        event_loop.set_control_flow(winit::event_loop::ControlFlow::Poll);
+        // Windows are only redrawn when they are invalidated, so the application can idle in between.
+        event_loop.set_control_flow(ControlFlow::Wait);
+
+        // UI files are only watched for changes during development.
+        let hot_reloader = if cfg!(debug_assertions) {
+            let proxy = event_loop.create_proxy();
+            HotReloader::new(move || {
+                let _ = proxy.send_event(AppEvent::FilesChanged);
+            })
+            .map_err(|e| eprintln!("Could not start watching UI files: {e}"))
+            .ok()
+        } else {
+            None
+        };
+
+        let proxy = event_loop.create_proxy();
+        let executor = Executor::new(move || {
+            let _ = proxy.send_event(AppEvent::TaskWoken);
+        });
+
+        let clipboard = Clipboard::new(SystemClipboard::new());
 
         Application {
             event_loop,
             application: ApplicationInternal {
                 gl_config,
-                window_indices: HashMap::new(),
-                windows: Vec::new(),
-                keyboard_modifiers: Modifiers::default(),
+                dispatcher: Dispatcher::new(executor, hot_reloader, clipboard),
+                message_handler: None,
+                recorder: None,
+                replay: None,
+                listening_device_events: false,
             },
             initial_raw_window: raw_window,
         }
     }
 
+    /// Sets the `handler` of the messages sent through the application's [proxies](Application::proxy).
+    ///
+    /// The handler runs on the application's thread and can update the UI through the [`Store`].
+    pub fn with_message_handler(mut self, handler: impl FnMut(T, &Store) + 'static) -> Self {
+        self.application.message_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Sets the `handler` of the actions triggered by the windows' widgets, such as clicked buttons,
+    /// that the application does not handle itself.
+    pub fn with_action_handler(
+        mut self,
+        handler: impl FnMut(&mut Window, &str, &Store) + 'static,
+    ) -> Self {
+        self.application.dispatcher.action_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Sets the `handler` of the changes of the windows' states, such as entering fullscreen
+    /// or being maximized, whether they were made by the application or by the user.
+    pub fn with_window_change_handler(
+        mut self,
+        handler: impl FnMut(&mut Window, &WindowChange, &Store) + 'static,
+    ) -> Self {
+        self.application.dispatcher.window_change_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Sets the `handler` of the inputs received by the windows that did not trigger an action
+    /// in their widget tree, e.g. for tools drawing on a canvas.
+    pub fn with_input_handler(
+        mut self,
+        handler: impl FnMut(&mut Window, &Input, &Store) + 'static,
+    ) -> Self {
+        self.application.dispatcher.input_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Sets the `handler` of the files dropped from other applications on the windows'
+    /// [drop targets](crate::ui::DropTarget).
+    pub fn with_file_drop_handler(
+        mut self,
+        handler: impl FnMut(&mut Window, &FileDrop, &Store) + 'static,
+    ) -> Self {
+        self.application.dispatcher.file_drop_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Sets the `handler` of the [payloads](crate::ui::DragPayload) dragged from the windows' widgets
+    /// and dropped on their [drop targets](crate::ui::DropTarget). The handler receives the window
+    /// the payload was dropped on.
+    pub fn with_drag_drop_handler(
+        mut self,
+        handler: impl FnMut(&mut Window, &DragDrop, &Store) + 'static,
+    ) -> Self {
+        self.application.dispatcher.drag_drop_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Sets the `handler` of the gestures recognized by the windows' widgets
+    /// with [recognizers](crate::ui::GestureRecognizer), from touches or touchpads.
+    pub fn with_gesture_handler(
+        mut self,
+        handler: impl FnMut(&mut Window, &Gesture, &Store) + 'static,
+    ) -> Self {
+        self.application.dispatcher.gesture_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Accesses the clipboard through the given `provider` instead of the system's clipboard.
+    pub fn with_clipboard(mut self, provider: impl ClipboardProvider + 'static) -> Self {
+        self.application.dispatcher.clipboard = Clipboard::new(provider);
+        self
+    }
+
+    /// Records the inputs received by the application's windows to the file at `path`,
+    /// so that the session can be [replayed](Application::with_replay) later.
+    pub fn with_recording(mut self, path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        self.application.recorder = Recorder::new(path)
+            .map_err(|e| eprintln!("Could not record to {}: {e}", path.display()))
+            .ok();
+        self
+    }
+
+    /// Replays a `recording` of an earlier session, delivering every input to the window
+    /// opened in the same order and at the same time since the start as when it was recorded.
+    ///
+    /// Inputs from the user are ignored until the replay has finished. Recorded resizes are requested
+    /// from the OS instead of being delivered, and the windows keep the scale factor of their monitors.
+    pub fn with_replay(mut self, recording: Recording) -> Self {
+        self.application.replay = Some(Replay::new(recording));
+        self
+    }
+
+    /// Saves the application's windows to the `session_store` when they are closed,
+    /// and restores the saved windows when the application is run.
+    pub fn with_session_store(mut self, session_store: SessionStore) -> Self {
+        self.application.dispatcher.session_store = Some(session_store);
+        self
+    }
+
+    /// Returns a handle for spawning futures onto the application's event loop thread.
+    pub fn spawner(&self) -> Spawner {
+        self.application.dispatcher.executor.spawner()
+    }
+
+    /// Returns a handle for scheduling timers and debounced callbacks on the application's event loop thread.
+    pub fn scheduler(&self) -> Scheduler {
+        self.application.dispatcher.scheduler.clone()
+    }
+
+    /// Returns a handle for reading and writing the system's clipboard, or the one set with
+    /// [`with_clipboard`](Application::with_clipboard).
+    pub fn clipboard(&self) -> Clipboard {
+        self.application.dispatcher.clipboard.clone()
+    }
+
+    /// Returns a new proxy for sending messages to the application from other threads.
+    pub fn proxy(&self) -> ApplicationProxy<T> {
+        ApplicationProxy {
+            proxy: self.event_loop.create_proxy(),
+        }
+    }
+
     /// Runs the application on the calling thread.
     pub fn run(mut self) -> ! {
         let Application {
@@ -109,129 +318,254 @@ impl Application {
     }
 }
 
-impl Default for Application {
+impl<T: Send + 'static> Default for Application<T> {
     fn default() -> Self {
         Application::new()
     }
 }
 
-impl ApplicationInternal {
+impl<T: 'static> ApplicationProxy<T> {
+    /// Sends a `message` to the application, waking up its event loop.
+    ///
+    /// Returns the message back if the application is no longer running.
+    pub fn send(&self, message: T) -> Result<(), EventLoopClosed<T>> {
+        self.proxy
+            .send_event(AppEvent::Message(message))
+            .map_err(|EventLoopClosed(event)| match event {
+                AppEvent::Message(message) => EventLoopClosed(message),
+                _ => unreachable!(),
+            })
+    }
+}
+
+impl<T: 'static> Clone for ApplicationProxy<T> {
+    fn clone(&self) -> Self {
+        ApplicationProxy {
+            proxy: self.proxy.clone(),
+        }
+    }
+}
+
+impl<T: 'static> ApplicationInternal<T> {
     /// Opens the first window when the application is run.
     fn open_first_window(&mut self, title: &str, initial_raw_window: RawWindow) {
         let window = Window::from_initial_raw(title, initial_raw_window, &self.gl_config);
-        self.window_indices.insert(window.id(), 0);
-        self.windows.push(window);
+        self.dispatcher.add_window(window);
     }
 
-    /// Opens a new window.
-    fn open_window(&mut self, title: &str, event_loop: &ActiveEventLoop) {
-        let window = Window::new(title, event_loop, &self.gl_config);
-        self.window_indices.insert(window.id(), self.windows.len());
-        self.windows.push(window);
+    /// Delivers the replayed inputs that are due at `now`, and returns the time the next one is due, if any.
+    fn replay_due(&mut self, now: Instant, event_loop: &ActiveEventLoop) -> Option<Instant> {
+        let replay = self.replay.as_mut()?;
+        let events = replay.take_due(now);
+        let deadline = replay.next_deadline();
+        if deadline.is_none() {
+            self.replay = None;
+        }
+        let mut platform = EventLoopPlatform::new(event_loop, &self.gl_config);
+        for event in events {
+            let window_id = match self.dispatcher.window_order.get(event.window) {
+                Some(&window_id) => window_id,
+                None => continue,
+            };
+            match event.input {
+                // The OS decides the windows' sizes and scale factors, and reports them with real events.
+                Input::Resized(size) => {
+                    if let Some(window) = self.dispatcher.windows.get(&window_id) {
+                        window.request_inner_size(size);
+                    }
+                }
+                Input::ScaleFactorChanged(_) => {}
+                input => self.dispatcher.input(&mut platform, window_id, input, now),
+            }
+        }
+        deadline
+    }
+
+    /// Listens to mouse motion outside of the focused window while clicks go through a window,
+    /// to notice when the cursor may have left its pass-through widgets.
+    fn update_device_events(&mut self, event_loop: &ActiveEventLoop) {
+        let passing_through = self
+            .dispatcher
+            .windows
+            .values()
+            .any(Window::is_passing_through);
+        if passing_through != self.listening_device_events {
+            self.listening_device_events = passing_through;
+            event_loop.listen_device_events(match passing_through {
+                true => DeviceEvents::Always,
+                false => DeviceEvents::WhenFocused,
+            });
+        }
+    }
+
+    /// Records an `input` received by the window with the given `window_id`, if the session is being recorded.
+    fn record(&mut self, window_id: WindowId, input: &Input) {
+        let (recorder, window) = match (
+            &mut self.recorder,
+            self.dispatcher
+                .window_order
+                .iter()
+                .position(|id| *id == window_id),
+        ) {
+            (Some(recorder), Some(window)) => (recorder, window),
+            _ => return,
+        };
+        if let Err(e) = recorder.record(window, input) {
+            eprintln!("Could not record input, recording stopped: {e}");
+            self.recorder = None;
+        }
     }
 }
 
-impl ApplicationHandler for ApplicationInternal {
-    // Stuff only for rendering the example animation. Can be safely removed in an actual application.
-    fn new_events(&mut self, _: &ActiveEventLoop, cause: StartCause) {
-        if let StartCause::Poll { .. } = cause {
-            if !self.windows.is_empty() {
-                self.windows[0].request_redraw()
-            }
+impl<T: 'static> ApplicationHandler<AppEvent<T>> for ApplicationInternal<T> {
+    fn new_events(&mut self, event_loop: &ActiveEventLoop, cause: StartCause) {
+        if cause == StartCause::Init {
+            let mut platform = EventLoopPlatform::new(event_loop, &self.gl_config);
+            self.dispatcher.restore_session(&mut platform);
         }
     }
 
     fn resumed(&mut self, _event_loop: &ActiveEventLoop) {}
 
+    fn device_event(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        _device_id: DeviceId,
+        event: DeviceEvent,
+    ) {
+        let delta = match event {
+            DeviceEvent::MouseMotion { delta } => delta,
+            _ => return,
+        };
+        // Windows don't receive cursor events while clicks go through them,
+        // so they check the region under the cursor again once it moves.
+        for window in self.dispatcher.windows.values_mut() {
+            window.pass_through_motion(delta);
+        }
+        // The cursor doesn't move while it is locked, so the window receives the raw motion instead.
+        let locked = self
+            .dispatcher
+            .windows
+            .iter()
+            .find_map(|(id, window)| window.wants_mouse_motion().then_some(*id));
+        if let Some(window_id) = locked.filter(|_| self.replay.is_none()) {
+            let input = Input::MouseMotion {
+                delta_x: delta.0 as f32,
+                delta_y: delta.1 as f32,
+            };
+            self.record(window_id, &input);
+            let mut platform = EventLoopPlatform::new(event_loop, &self.gl_config);
+            self.dispatcher
+                .input(&mut platform, window_id, input, Instant::now());
+        }
+    }
+
+    fn user_event(&mut self, _event_loop: &ActiveEventLoop, event: AppEvent<T>) {
+        match event {
+            AppEvent::Message(message) => {
+                if let Some(handler) = &mut self.message_handler {
+                    handler(message, &self.dispatcher.store);
+                }
+            }
+            // Changed files are reloaded and woken tasks are polled in `about_to_wait`;
+            // these events only wake up the loop.
+            AppEvent::FilesChanged | AppEvent::TaskWoken => {}
+        }
+    }
+
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        let now = Instant::now();
+        let next_replayed = self.replay_due(now, event_loop);
+        let dispatcher = &mut self.dispatcher;
+        dispatcher.reload_changed_uis();
+        dispatcher.scheduler.run_due(now);
+        dispatcher.executor.run_ready();
+        dispatcher.notify_window_changes();
+        self.update_device_events(event_loop);
+        let dispatcher = &mut self.dispatcher;
+        for window in dispatcher.windows.values_mut() {
+            window.apply_cursor(event_loop);
+        }
+        dispatcher.redraw_invalidated_windows();
+
+        // Sleep until the next timer or animation frame is due, or until another event arrives.
+        let deadline = [
+            dispatcher.scheduler.next_deadline(),
+            dispatcher.animate(now),
+            dispatcher.auto_scroll(now),
+            dispatcher.recognize_long_presses(now),
+            next_replayed,
+        ]
+        .into_iter()
+        .flatten()
+        .min();
+        // The handlers of window changes and long presses may have opened dialogs.
+        let mut platform = EventLoopPlatform::new(event_loop, &self.gl_config);
+        dispatcher.open_requested_dialogs(&mut platform);
+        event_loop.set_control_flow(match deadline {
+            Some(deadline) => ControlFlow::WaitUntil(deadline),
+            None => ControlFlow::Wait,
+        });
+    }
+
     fn window_event(
         &mut self,
         event_loop: &ActiveEventLoop,
         window_id: WindowId,
         event: WindowEvent,
     ) {
-        // Stuff only for rendering the example animation. Can be safely removed in an actual application.
-        let frame_start = std::time::Instant::now();
-        let window_count = self.windows.len();
-
-        let window_index = match self.window_indices.get(&window_id) {
-            Some(index) => *index,
+        let window = match self.dispatcher.windows.get(&window_id) {
+            Some(window) => window,
             None => return,
         };
-        let window = &mut self.windows[window_index];
+        if let Some(input) = Input::from_window_event(&event, window.scale_factor()) {
+            // The user's input would interfere with the replayed one, but resizes still have to be handled.
+            if self.replay.is_some() && input.is_user_input() {
+                return;
+            }
+            self.record(window_id, &input);
+            let mut platform = EventLoopPlatform::new(event_loop, &self.gl_config);
+            self.dispatcher
+                .input(&mut platform, window_id, input, Instant::now());
+            return;
+        }
+        let mut platform = EventLoopPlatform::new(event_loop, &self.gl_config);
         match event {
             WindowEvent::ActivationTokenDone { .. } => {}
-            WindowEvent::Resized(physical_size) => window.resize(physical_size),
             WindowEvent::Moved(_) => {}
             WindowEvent::CloseRequested => {
-                self.window_indices.remove(&window_id);
-                self.windows.remove(window_index);
-                if self.windows.is_empty() {
-                    event_loop.exit();
-                    return;
-                }
-                for i in window_index..self.windows.len() {
-                    let window = &mut self.windows[i];
-                    let id = window.id();
-                    self.window_indices.insert(id, i);
-                }
+                self.dispatcher.close_requested(&mut platform, window_id)
             }
             WindowEvent::Destroyed => {}
-            WindowEvent::DroppedFile(_) => {}
-            WindowEvent::HoveredFile(_) => {}
-            WindowEvent::HoveredFileCancelled => {}
-            WindowEvent::Focused(_) => {}
-            WindowEvent::KeyboardInput {
-                event:
-                    KeyEvent {
-                        logical_key,
-                        state: ElementState::Released,
-                        repeat: false,
-                        ..
-                    },
-                ..
-            } => {
-                if logical_key == "q" {
-                    self.window_event(event_loop, window_id, WindowEvent::CloseRequested);
-                } else if logical_key == "a" {
-                    let title = format!("Window {}", window_count);
-                    self.open_window(title.as_str(), event_loop);
+            WindowEvent::Focused(focused) => {
+                if focused {
+                    self.dispatcher.focus_modal_dialog(window_id);
                 }
             }
-            WindowEvent::KeyboardInput { .. } => {}
-            WindowEvent::ModifiersChanged(new_mods) => self.keyboard_modifiers = new_mods,
-            WindowEvent::Ime(_) => {}
-            WindowEvent::CursorMoved { .. } => {}
             WindowEvent::CursorEntered { .. } => {}
-            WindowEvent::CursorLeft { .. } => {}
-            WindowEvent::MouseWheel { .. } => {}
-            WindowEvent::MouseInput { .. } => {}
-            WindowEvent::PinchGesture { .. } => {}
-            WindowEvent::PanGesture { .. } => {}
-            WindowEvent::DoubleTapGesture { .. } => {}
-            WindowEvent::RotationGesture { .. } => {}
             WindowEvent::TouchpadPressure { .. } => {}
             WindowEvent::AxisMotion { .. } => {}
-            WindowEvent::Touch(_) => {}
-            WindowEvent::ScaleFactorChanged { .. } => window.request_redraw(),
             WindowEvent::ThemeChanged(_) => {}
             WindowEvent::Occluded(_) => {}
-            WindowEvent::RedrawRequested => {
-                // Stuff only for rendering the example animation. Can be safely removed in an actual application.
-                let frame_duration = std::time::Duration::from_secs_f64(1.0 / 60.0);
-                if frame_start - window.previous_frame_start > frame_duration {
-                    window.previous_frame_start = frame_start;
-                    window.frame += 1;
-                    let frame = window.frame;
-                    window.reset_canvas(skia_safe::Color::WHITE);
-                    window.draw(|canvas| {
-                        renderer::render_frame(frame % 360, 60, 60, canvas);
-                    });
-                }
-                let next_window_index = window_index + 1;
-                if next_window_index < window_count {
-                    self.windows[next_window_index].request_redraw();
-                }
-            }
+            // Handled as `Input`s above.
+            WindowEvent::Resized(_)
+            | WindowEvent::KeyboardInput { .. }
+            | WindowEvent::ModifiersChanged(_)
+            | WindowEvent::Ime(_)
+            | WindowEvent::CursorMoved { .. }
+            | WindowEvent::CursorLeft { .. }
+            | WindowEvent::MouseWheel { .. }
+            | WindowEvent::MouseInput { .. }
+            | WindowEvent::HoveredFile(_)
+            | WindowEvent::HoveredFileCancelled
+            | WindowEvent::DroppedFile(_)
+            | WindowEvent::Touch(_)
+            | WindowEvent::PinchGesture { .. }
+            | WindowEvent::PanGesture { .. }
+            | WindowEvent::RotationGesture { .. }
+            | WindowEvent::DoubleTapGesture { .. }
+            | WindowEvent::ScaleFactorChanged { .. } => {}
+            WindowEvent::RedrawRequested => self.dispatcher.redraw(window_id),
         }
     }
 }
@@ -0,0 +1,307 @@
+use crate::core::pixels::{read_rgba, rgba_info};
+
+use skia_safe::{images, Data, ISize, Image};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::fmt::{self, Debug, Formatter};
+use std::rc::Rc;
+
+/// A handle for reading and writing the clipboard shared with other applications.
+///
+/// The clipboard is accessed through a [`ClipboardProvider`]: the [system's clipboard](SystemClipboard)
+/// in the application, and an [in-memory one](MemoryClipboard) in a [`Harness`](crate::core::Harness).
+///
+/// Clipboards are cheap to clone; all clones share the same provider.
+#[derive(Clone)]
+pub struct Clipboard {
+    provider: Rc<RefCell<dyn ClipboardProvider>>,
+}
+
+/// The content of the clipboard.
+#[derive(Debug, Clone)]
+pub enum ClipboardContent {
+    /// Plain text.
+    Text(String),
+    /// Rich text, along with its plain text version for applications that don't support HTML.
+    Html {
+        /// The HTML markup.
+        html: String,
+        /// The plain text version of the markup.
+        alt_text: String,
+    },
+    /// An image.
+    Image(Image),
+}
+
+/// The kinds of [content](ClipboardContent) the clipboard can hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(missing_docs)]
+pub enum ClipboardKind {
+    Text,
+    Html,
+    Image,
+}
+
+/// A clipboard implementation.
+pub trait ClipboardProvider {
+    /// Returns the content of the given `kind` on the clipboard, if it holds any.
+    ///
+    /// The plain text version of HTML content is also returned as [text](ClipboardKind::Text).
+    fn get(&mut self, kind: ClipboardKind) -> Option<ClipboardContent>;
+
+    /// Replaces the content of the clipboard.
+    fn set(&mut self, content: ClipboardContent);
+}
+
+/// The clipboard of the desktop, shared with other applications.
+///
+/// It is opened the first time it is used. If the platform's clipboard is not available,
+/// the error is printed and the clipboard stays empty.
+#[derive(Default)]
+pub struct SystemClipboard {
+    clipboard: Option<arboard::Clipboard>,
+    unavailable: bool,
+}
+
+/// A clipboard only kept in memory, for tests.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryClipboard {
+    content: Option<ClipboardContent>,
+}
+
+impl Clipboard {
+    /// Creates a clipboard accessed through the given `provider`.
+    pub fn new(provider: impl ClipboardProvider + 'static) -> Self {
+        Clipboard {
+            provider: Rc::new(RefCell::new(provider)),
+        }
+    }
+
+    /// Returns the text on the clipboard, if it holds any.
+    pub fn text(&self) -> Option<String> {
+        match self.provider.borrow_mut().get(ClipboardKind::Text)? {
+            ClipboardContent::Text(text) => Some(text),
+            ClipboardContent::Html { alt_text, .. } => Some(alt_text),
+            ClipboardContent::Image(_) => None,
+        }
+    }
+
+    /// Puts plain `text` on the clipboard.
+    pub fn set_text(&self, text: impl Into<String>) {
+        self.set(ClipboardContent::Text(text.into()));
+    }
+
+    /// Returns the HTML markup on the clipboard, if it holds any.
+    pub fn html(&self) -> Option<String> {
+        match self.provider.borrow_mut().get(ClipboardKind::Html)? {
+            ClipboardContent::Html { html, .. } => Some(html),
+            _ => None,
+        }
+    }
+
+    /// Puts `html` markup on the clipboard, along with its plain text version.
+    pub fn set_html(&self, html: impl Into<String>, alt_text: impl Into<String>) {
+        self.set(ClipboardContent::Html {
+            html: html.into(),
+            alt_text: alt_text.into(),
+        });
+    }
+
+    /// Returns the image on the clipboard, if it holds one.
+    pub fn image(&self) -> Option<Image> {
+        match self.provider.borrow_mut().get(ClipboardKind::Image)? {
+            ClipboardContent::Image(image) => Some(image),
+            _ => None,
+        }
+    }
+
+    /// Puts an `image` on the clipboard.
+    pub fn set_image(&self, image: Image) {
+        self.set(ClipboardContent::Image(image));
+    }
+
+    /// Replaces the content of the clipboard.
+    pub fn set(&self, content: ClipboardContent) {
+        self.provider.borrow_mut().set(content);
+    }
+}
+
+impl Default for Clipboard {
+    /// Creates a clipboard kept in memory.
+    fn default() -> Self {
+        Clipboard::new(MemoryClipboard::default())
+    }
+}
+
+impl Debug for Clipboard {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Clipboard").finish_non_exhaustive()
+    }
+}
+
+impl SystemClipboard {
+    /// Creates a handle to the system's clipboard, which is opened the first time it is used.
+    pub fn new() -> Self {
+        SystemClipboard::default()
+    }
+
+    /// Returns the system's clipboard, opening it if needed.
+    fn clipboard(&mut self) -> Option<&mut arboard::Clipboard> {
+        if self.clipboard.is_none() && !self.unavailable {
+            match arboard::Clipboard::new() {
+                Ok(clipboard) => self.clipboard = Some(clipboard),
+                Err(e) => {
+                    eprintln!("Could not open the clipboard: {e}");
+                    self.unavailable = true;
+                }
+            }
+        }
+        self.clipboard.as_mut()
+    }
+}
+
+impl ClipboardProvider for SystemClipboard {
+    fn get(&mut self, kind: ClipboardKind) -> Option<ClipboardContent> {
+        let clipboard = self.clipboard()?;
+        // An empty clipboard or one holding another kind of content is not an error worth reporting.
+        let content = match kind {
+            ClipboardKind::Text => ClipboardContent::Text(clipboard.get_text().ok()?),
+            ClipboardKind::Html => {
+                let html = clipboard.get().html().ok()?;
+                let alt_text = clipboard.get_text().unwrap_or_default();
+                ClipboardContent::Html { html, alt_text }
+            }
+            ClipboardKind::Image => {
+                ClipboardContent::Image(from_image_data(&clipboard.get_image().ok()?)?)
+            }
+        };
+        Some(content)
+    }
+
+    fn set(&mut self, content: ClipboardContent) {
+        let clipboard = match self.clipboard() {
+            Some(clipboard) => clipboard,
+            None => return,
+        };
+        let result = match content {
+            ClipboardContent::Text(text) => clipboard.set_text(text),
+            ClipboardContent::Html { html, alt_text } => clipboard.set_html(html, Some(alt_text)),
+            ClipboardContent::Image(image) => match to_image_data(&image) {
+                Some(image) => clipboard.set_image(image),
+                None => {
+                    eprintln!("Could not read the pixels of the image to copy");
+                    return;
+                }
+            },
+        };
+        if let Err(e) = result {
+            eprintln!("Could not write to the clipboard: {e}");
+        }
+    }
+}
+
+impl MemoryClipboard {
+    /// Creates an empty clipboard.
+    pub fn new() -> Self {
+        MemoryClipboard::default()
+    }
+}
+
+impl ClipboardProvider for MemoryClipboard {
+    fn get(&mut self, kind: ClipboardKind) -> Option<ClipboardContent> {
+        let content = self.content.as_ref()?;
+        let matches = matches!(
+            (kind, content),
+            (
+                ClipboardKind::Text,
+                ClipboardContent::Text(_) | ClipboardContent::Html { .. }
+            ) | (ClipboardKind::Html, ClipboardContent::Html { .. })
+                | (ClipboardKind::Image, ClipboardContent::Image(_))
+        );
+        matches.then(|| content.clone())
+    }
+
+    fn set(&mut self, content: ClipboardContent) {
+        self.content = Some(content);
+    }
+}
+
+/// Returns the pixels of an `image` as unpremultiplied RGBA, the way the clipboard holds them.
+fn to_image_data(image: &Image) -> Option<arboard::ImageData<'static>> {
+    let pixels = read_rgba(image)?;
+    Some(arboard::ImageData {
+        width: image.width() as usize,
+        height: image.height() as usize,
+        bytes: Cow::Owned(pixels),
+    })
+}
+
+/// Creates an image from the unpremultiplied RGBA pixels held by the clipboard.
+fn from_image_data(image: &arboard::ImageData) -> Option<Image> {
+    let info = rgba_info(ISize::new(image.width as i32, image.height as i32));
+    let row_bytes = info.min_row_bytes();
+    images::raster_from_data(&info, Data::new_copy(&image.bytes), row_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_is_read_as_text_only() {
+        let clipboard = Clipboard::new(MemoryClipboard::new());
+        assert_eq!(clipboard.text(), None);
+
+        clipboard.set_text("plain");
+
+        assert_eq!(clipboard.text().as_deref(), Some("plain"));
+        assert_eq!(clipboard.html(), None);
+        assert!(clipboard.image().is_none());
+    }
+
+    #[test]
+    fn html_is_also_read_as_its_plain_text() {
+        let clipboard = Clipboard::new(MemoryClipboard::new());
+
+        clipboard.set_html("<i>rich</i>", "rich");
+
+        assert_eq!(clipboard.html().as_deref(), Some("<i>rich</i>"));
+        assert_eq!(clipboard.text().as_deref(), Some("rich"));
+        assert!(clipboard.image().is_none());
+    }
+
+    #[test]
+    fn images_are_read_as_images_only() {
+        let clipboard = Clipboard::new(MemoryClipboard::new());
+        let mut surface = skia_safe::surfaces::raster_n32_premul((4, 3)).unwrap();
+
+        clipboard.set_image(surface.image_snapshot());
+
+        assert_eq!(
+            clipboard.image().map(|image| image.dimensions()),
+            Some(ISize::new(4, 3))
+        );
+        assert_eq!(clipboard.text(), None);
+        assert_eq!(clipboard.html(), None);
+    }
+
+    #[test]
+    fn clones_share_the_content() {
+        let clipboard = Clipboard::default();
+        let clone = clipboard.clone();
+
+        clone.set_text("shared");
+
+        assert_eq!(clipboard.text().as_deref(), Some("shared"));
+    }
+
+    #[test]
+    fn memory_clipboards_only_return_the_requested_kinds() {
+        let mut memory = MemoryClipboard::new();
+        memory.set(ClipboardContent::Text("text".into()));
+
+        assert!(memory.get(ClipboardKind::Text).is_some());
+        assert!(memory.get(ClipboardKind::Html).is_none());
+        assert!(memory.get(ClipboardKind::Image).is_none());
+    }
+}
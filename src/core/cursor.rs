@@ -0,0 +1,142 @@
+use crate::ui::ImageContent;
+
+use skia_safe::{
+    surfaces, svg, AlphaType, ColorType, CubicResampler, ISize, Image, ImageInfo, Point, Rect, Size,
+};
+use winit::window::{CursorIcon, CustomCursorSource};
+
+/// The largest side of a custom cursor, in physical pixels, that all platforms accept.
+const MAX_CURSOR_SIZE: f32 = 256.0;
+
+/// The cursor shown while the mouse is over a [`Window`](crate::core::Window).
+#[derive(Debug, Clone)]
+pub enum Cursor {
+    /// One of the platform's standard cursors.
+    Icon(CursorIcon),
+    /// A cursor drawn by the application.
+    Custom(CustomCursor),
+}
+
+/// A cursor drawn from a raster image or an SVG document.
+///
+/// Its size and hotspot are in logical pixels: the cursor is rasterized again for the window's
+/// scale factor, so that it stays crisp when the window moves to a monitor of a different density.
+#[derive(Debug, Clone)]
+pub struct CustomCursor {
+    image: ImageContent,
+    size: Size,
+    hotspot: Point,
+}
+
+impl Default for Cursor {
+    fn default() -> Self {
+        Cursor::Icon(CursorIcon::Default)
+    }
+}
+
+impl From<CursorIcon> for Cursor {
+    fn from(icon: CursorIcon) -> Self {
+        Cursor::Icon(icon)
+    }
+}
+
+impl From<CustomCursor> for Cursor {
+    fn from(cursor: CustomCursor) -> Self {
+        Cursor::Custom(cursor)
+    }
+}
+
+impl CustomCursor {
+    /// Creates a cursor from a raster `image`, shown at its size in logical pixels.
+    ///
+    /// The `hotspot` is the point of the image that clicks, from its top-left corner.
+    pub fn from_image(image: Image, hotspot: impl Into<Point>) -> Self {
+        let size = Size::new(image.width() as f32, image.height() as f32);
+        CustomCursor {
+            image: ImageContent::Raster(image),
+            size,
+            hotspot: hotspot.into(),
+        }
+    }
+
+    /// Creates a cursor from an SVG document, shown at the given `size` in logical pixels.
+    ///
+    /// The `hotspot` is the point of the document that clicks, from its top-left corner.
+    pub fn from_svg(dom: svg::Dom, size: impl Into<Size>, hotspot: impl Into<Point>) -> Self {
+        CustomCursor {
+            image: ImageContent::Svg(dom),
+            size: size.into(),
+            hotspot: hotspot.into(),
+        }
+    }
+
+    /// Returns the cursor's size in logical pixels.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Returns the point of the cursor that clicks, in logical pixels from its top-left corner.
+    pub fn hotspot(&self) -> Point {
+        self.hotspot
+    }
+
+    /// Rasterizes the cursor for a window with the given `scale_factor`.
+    pub(super) fn rasterize(&self, scale_factor: f64) -> Option<CustomCursorSource> {
+        // Cursors that would be too large for some platforms are scaled down, hotspot included.
+        let largest_side = self.size.width.max(self.size.height) * scale_factor as f32;
+        let scale = match largest_side > MAX_CURSOR_SIZE {
+            true => MAX_CURSOR_SIZE / largest_side * scale_factor as f32,
+            false => scale_factor as f32,
+        };
+        let size = ISize::new(
+            (self.size.width * scale).round().max(1.0) as i32,
+            (self.size.height * scale).round().max(1.0) as i32,
+        );
+
+        let mut surface = match surfaces::raster_n32_premul(size) {
+            Some(surface) => surface,
+            None => {
+                eprintln!("Could not create a {}x{} cursor", size.width, size.height);
+                return None;
+            }
+        };
+        let canvas = surface.canvas();
+        let dst = Rect::from_isize(size);
+        match &self.image {
+            ImageContent::Raster(image) => {
+                canvas.draw_image_rect_with_sampling_options(
+                    image,
+                    None,
+                    dst,
+                    CubicResampler::mitchell(),
+                    &Default::default(),
+                );
+            }
+            ImageContent::Svg(dom) => {
+                let mut dom = dom.clone();
+                dom.set_container_size(self.size);
+                canvas.scale((scale, scale));
+                dom.render(canvas);
+            }
+        }
+
+        // Cursors are expected as RGBA pixels without premultiplied alpha.
+        let info = ImageInfo::new(size, ColorType::RGBA8888, AlphaType::Unpremul, None);
+        let mut pixels = vec![0; info.compute_min_byte_size()];
+        if !surface.read_pixels(&info, &mut pixels, info.min_row_bytes(), (0, 0)) {
+            eprintln!("Could not read the pixels of the cursor");
+            return None;
+        }
+        let hotspot_x = (self.hotspot.x * scale).clamp(0.0, size.width as f32 - 1.0);
+        let hotspot_y = (self.hotspot.y * scale).clamp(0.0, size.height as f32 - 1.0);
+        winit::window::CustomCursor::from_rgba(
+            pixels,
+            size.width as u16,
+            size.height as u16,
+            hotspot_x as u16,
+            hotspot_y as u16,
+        )
+        .map_err(|e| eprintln!("Invalid cursor: {e}"))
+        .ok()
+    }
+}
@@ -0,0 +1,118 @@
+use crate::core::FrameStats;
+use crate::ui::default_font;
+
+use skia_safe::gpu::ResourceCacheUsage;
+use skia_safe::{Canvas, Color, Paint, PaintStyle, Point, Rect};
+use std::time::Duration;
+
+const FONT_SIZE: f32 = 12.0;
+const LINE_HEIGHT: f32 = FONT_SIZE * 1.4;
+const PADDING: f32 = 8.0;
+const WIDTH: f32 = 240.0;
+const GRAPH_HEIGHT: f32 = 48.0;
+/// Frame time shown at the top of the graph.
+const GRAPH_MAX: Duration = Duration::from_millis(50);
+/// Frame time of a 60 FPS display, marked in the graph.
+const GRAPH_TARGET: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+/// Draws the frame statistics and GPU resource cache usage of a window in its top-left corner.
+pub(super) fn draw_overlay(
+    canvas: &Canvas,
+    stats: &FrameStats,
+    cache_usage: ResourceCacheUsage,
+    cache_limit: usize,
+) {
+    let fps = match stats.fps() {
+        Some(fps) => format!("{fps:.1} FPS"),
+        None => "Idle".to_string(),
+    };
+    let lines = [
+        fps,
+        format!(
+            "Frame: {} (avg {})",
+            millis(stats.last_frame_time()),
+            millis(stats.average_frame_time())
+        ),
+        format!("Draw (CPU): {}", millis(stats.last_draw_time())),
+        format!("Flush and submit: {}", millis(stats.last_flush_time())),
+        format!(
+            "GPU cache: {} resources, {:.1} / {:.0} MB",
+            cache_usage.resource_count,
+            megabytes(cache_usage.resource_bytes),
+            megabytes(cache_limit)
+        ),
+    ];
+
+    let text_height = lines.len() as f32 * LINE_HEIGHT;
+    let background = Rect::from_xywh(
+        PADDING,
+        PADDING,
+        WIDTH,
+        text_height + GRAPH_HEIGHT + PADDING * 3.0,
+    );
+    let mut paint = Paint::default();
+    paint.set_anti_alias(true);
+    paint.set_color(Color::from_argb(0xcc, 0x10, 0x10, 0x10));
+    canvas.draw_round_rect(background, 4.0, 4.0, &paint);
+
+    let font = default_font(FONT_SIZE);
+    paint.set_color(Color::WHITE);
+    let left = background.left + PADDING;
+    for (i, line) in lines.iter().enumerate() {
+        let baseline = background.top + PADDING + FONT_SIZE + i as f32 * LINE_HEIGHT;
+        canvas.draw_str(line, (left, baseline), &font, &paint);
+    }
+
+    let graph = Rect::from_xywh(
+        left,
+        background.top + PADDING * 2.0 + text_height,
+        WIDTH - PADDING * 2.0,
+        GRAPH_HEIGHT,
+    );
+    draw_graph(canvas, graph, stats);
+}
+
+/// Draws the recent frame intervals as bars, green when they are within the 60 FPS budget and red otherwise.
+fn draw_graph(canvas: &Canvas, bounds: Rect, stats: &FrameStats) {
+    let height_of =
+        |time: Duration| (time.as_secs_f32() / GRAPH_MAX.as_secs_f32()).min(1.0) * bounds.height();
+
+    let mut paint = Paint::default();
+    paint.set_color(Color::from_argb(0x40, 0xff, 0xff, 0xff));
+    canvas.draw_rect(bounds, &paint);
+
+    let intervals = stats.frame_intervals();
+    let bar_width = bounds.width() / intervals.len().max(1) as f32;
+    for (i, interval) in intervals.enumerate() {
+        paint.set_color(if interval <= GRAPH_TARGET + Duration::from_millis(1) {
+            Color::from_rgb(0x4c, 0xaf, 0x50)
+        } else {
+            Color::from_rgb(0xf4, 0x43, 0x36)
+        });
+        let left = bounds.left + i as f32 * bar_width;
+        let bar = Rect::from_ltrb(
+            left,
+            bounds.bottom - height_of(interval),
+            left + bar_width,
+            bounds.bottom,
+        );
+        canvas.draw_rect(bar, &paint);
+    }
+
+    paint.set_style(PaintStyle::Stroke);
+    paint.set_color(Color::WHITE);
+    let target = bounds.bottom - height_of(GRAPH_TARGET);
+    canvas.draw_line(
+        Point::new(bounds.left, target),
+        Point::new(bounds.right, target),
+        &paint,
+    );
+}
+
+fn millis(duration: Duration) -> String {
+    format!("{:.2} ms", duration.as_secs_f64() * 1000.0)
+}
+
+fn megabytes(bytes: usize) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0)
+}
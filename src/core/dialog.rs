@@ -0,0 +1,341 @@
+use crate::ui::default_font;
+
+use skia_safe::{Canvas, Color, Contains, Paint, PaintStyle, Rect};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use winit::dpi::{LogicalPosition, LogicalSize, PhysicalPosition};
+use winit::event::ElementState;
+use winit::keyboard::{Key, NamedKey};
+use winit::window::{WindowAttributes, WindowId};
+
+/// Logical size of every dialog window.
+const DIALOG_SIZE: LogicalSize<f32> = LogicalSize::new(360.0, 150.0);
+/// Logical size of a dialog button.
+const BUTTON_SIZE: LogicalSize<f32> = LogicalSize::new(80.0, 28.0);
+const PADDING: f32 = 20.0;
+const FONT_SIZE: f32 = 14.0;
+
+/// A description of a modal dialog that can be opened on top of a window.
+#[derive(Debug, Clone)]
+pub struct Dialog {
+    title: String,
+    message: String,
+    kind: DialogKind,
+}
+
+/// The preset a [`Dialog`] is built from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DialogKind {
+    /// A message with a single "OK" button.
+    Alert,
+    /// A question with "OK" and "Cancel" buttons.
+    Confirm,
+    /// A question with a text field, pre-filled with the given value.
+    Prompt(String),
+}
+
+/// The user's choice in a [`Dialog`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DialogResponse {
+    /// The dialog was accepted.
+    Ok,
+    /// The dialog was dismissed, either with the "Cancel" button, the `Escape` key
+    /// or by closing its window.
+    Cancel,
+    /// A [prompt](DialogKind::Prompt) was accepted with the given text.
+    Text(String),
+}
+
+/// A handle to an open [`Dialog`].
+///
+/// It is a [`Future`] that resolves to the user's choice once the dialog is closed.
+/// Dropping the handle does not close the dialog.
+#[derive(Debug)]
+pub struct DialogHandle {
+    shared: Arc<Mutex<SharedResponse>>,
+}
+
+#[derive(Debug, Default)]
+struct SharedResponse {
+    response: Option<DialogResponse>,
+    waker: Option<Waker>,
+}
+
+/// The state of a dialog window managed by the application.
+pub(super) struct DialogState {
+    dialog: Dialog,
+    parent: WindowId,
+    input: String,
+    cursor_position: LogicalPosition<f32>,
+    shared: Arc<Mutex<SharedResponse>>,
+}
+
+/// A clickable area of a dialog.
+#[derive(Clone, Copy)]
+enum Button {
+    Ok,
+    Cancel,
+}
+
+impl Dialog {
+    /// Creates an alert with a single "OK" button.
+    pub fn alert(title: impl Into<String>, message: impl Into<String>) -> Self {
+        Dialog::new(title, message, DialogKind::Alert)
+    }
+
+    /// Creates a confirmation dialog with "OK" and "Cancel" buttons.
+    pub fn confirm(title: impl Into<String>, message: impl Into<String>) -> Self {
+        Dialog::new(title, message, DialogKind::Confirm)
+    }
+
+    /// Creates a prompt asking the user for a line of text.
+    pub fn prompt(
+        title: impl Into<String>,
+        message: impl Into<String>,
+        default_value: impl Into<String>,
+    ) -> Self {
+        Dialog::new(title, message, DialogKind::Prompt(default_value.into()))
+    }
+
+    fn new(title: impl Into<String>, message: impl Into<String>, kind: DialogKind) -> Self {
+        Dialog {
+            title: title.into(),
+            message: message.into(),
+            kind,
+        }
+    }
+
+    /// Returns the dialog's title.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Returns the dialog's kind.
+    pub fn kind(&self) -> &DialogKind {
+        &self.kind
+    }
+}
+
+impl DialogHandle {
+    /// Returns the user's choice if the dialog has already been closed.
+    pub fn try_response(&self) -> Option<DialogResponse> {
+        self.shared.lock().unwrap().response.clone()
+    }
+}
+
+impl Future for DialogHandle {
+    type Output = DialogResponse;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared = self.shared.lock().unwrap();
+        match shared.response.clone() {
+            Some(response) => Poll::Ready(response),
+            None => {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl DialogState {
+    /// Creates the state of a `dialog` parented to the `parent` window,
+    /// along with a handle resolving to the user's choice.
+    pub(super) fn new(dialog: Dialog, parent: WindowId) -> (Self, DialogHandle) {
+        let shared = Arc::new(Mutex::new(SharedResponse::default()));
+        let input = match &dialog.kind {
+            DialogKind::Prompt(default_value) => default_value.clone(),
+            _ => String::new(),
+        };
+        let state = DialogState {
+            dialog,
+            parent,
+            input,
+            cursor_position: LogicalPosition::new(-1.0, -1.0),
+            shared: shared.clone(),
+        };
+        (state, DialogHandle { shared })
+    }
+
+    /// Returns the ID of the window blocked by this dialog.
+    pub(super) fn parent(&self) -> WindowId {
+        self.parent
+    }
+
+    /// Attributes for the dialog's window, centered over the parent window
+    /// whose outer bounds are given by `parent_position` and `parent_size`.
+    pub(super) fn window_attrs(
+        &self,
+        parent_position: Option<PhysicalPosition<i32>>,
+        parent_size: LogicalSize<f32>,
+        scale_factor: f64,
+    ) -> WindowAttributes {
+        let attrs = WindowAttributes::default()
+            .with_title(self.dialog.title.as_str())
+            .with_inner_size(DIALOG_SIZE)
+            .with_resizable(false);
+        match parent_position {
+            Some(position) => {
+                let position = position.to_logical::<f32>(scale_factor);
+                attrs.with_position(LogicalPosition::new(
+                    position.x + (parent_size.width - DIALOG_SIZE.width) / 2.0,
+                    position.y + (parent_size.height - DIALOG_SIZE.height) / 2.0,
+                ))
+            }
+            None => attrs,
+        }
+    }
+
+    /// Updates the cursor position, given in logical pixels.
+    pub(super) fn set_cursor_position(&mut self, position: LogicalPosition<f32>) {
+        self.cursor_position = position;
+    }
+
+    /// Handles a `key` press or release producing the given `text`,
+    /// returning the user's choice if it closes the dialog.
+    pub(super) fn handle_key(
+        &mut self,
+        key: &Key,
+        state: ElementState,
+        text: Option<&str>,
+    ) -> Option<DialogResponse> {
+        if state != ElementState::Pressed {
+            return None;
+        }
+        match key {
+            Key::Named(NamedKey::Enter) => Some(self.response_for(Button::Ok)),
+            Key::Named(NamedKey::Escape) => Some(self.response_for(Button::Cancel)),
+            Key::Named(NamedKey::Backspace) if self.is_prompt() => {
+                self.input.pop();
+                None
+            }
+            _ => {
+                if let (true, Some(text)) = (self.is_prompt(), text) {
+                    self.input.extend(text.chars().filter(|c| !c.is_control()));
+                }
+                None
+            }
+        }
+    }
+
+    /// Handles a left mouse button click at the current cursor position,
+    /// returning the user's choice if it closes the dialog.
+    pub(super) fn handle_click(&self) -> Option<DialogResponse> {
+        self.buttons()
+            .into_iter()
+            .find(|(_, rect)| rect.contains(self.cursor_position_as_point()))
+            .map(|(button, _)| self.response_for(button))
+    }
+
+    /// Resolves the dialog's [handle](DialogHandle) with the user's choice.
+    pub(super) fn respond(&self, response: DialogResponse) {
+        let mut shared = self.shared.lock().unwrap();
+        if shared.response.is_none() {
+            shared.response = Some(response);
+            if let Some(waker) = shared.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+
+    /// Draws the dialog on the `canvas`, using logical pixels.
+    pub(super) fn draw(&self, canvas: &Canvas) {
+        let font = default_font(FONT_SIZE);
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+
+        canvas.clear(Color::from_rgb(0xf2, 0xf2, 0xf2));
+
+        paint.set_color(Color::BLACK);
+        canvas.draw_str(
+            &self.dialog.message,
+            (PADDING, PADDING + FONT_SIZE),
+            &font,
+            &paint,
+        );
+
+        if self.is_prompt() {
+            let field = Rect::from_xywh(
+                PADDING,
+                PADDING * 2.0 + FONT_SIZE,
+                DIALOG_SIZE.width - PADDING * 2.0,
+                BUTTON_SIZE.height,
+            );
+            paint.set_color(Color::WHITE);
+            canvas.draw_rect(field, &paint);
+            paint.set_style(PaintStyle::Stroke);
+            paint.set_color(Color::DARK_GRAY);
+            canvas.draw_rect(field, &paint);
+            paint.set_style(PaintStyle::Fill);
+            paint.set_color(Color::BLACK);
+            let baseline = field.center_y() + FONT_SIZE / 2.0 - 2.0;
+            canvas.save();
+            canvas.clip_rect(field, None, true);
+            canvas.draw_str(&self.input, (field.left + 6.0, baseline), &font, &paint);
+            canvas.restore();
+        }
+
+        let cursor = self.cursor_position_as_point();
+        for (button, rect) in self.buttons() {
+            let label = match button {
+                Button::Ok => "OK",
+                Button::Cancel => "Cancel",
+            };
+            let background = if rect.contains(cursor) {
+                Color::from_rgb(0xd0, 0xd8, 0xe8)
+            } else {
+                Color::from_rgb(0xe0, 0xe0, 0xe0)
+            };
+            paint.set_color(background);
+            canvas.draw_round_rect(rect, 4.0, 4.0, &paint);
+            paint.set_color(Color::BLACK);
+            let (width, _) = font.measure_str(label, Some(&paint));
+            let origin = (
+                rect.center_x() - width / 2.0,
+                rect.center_y() + FONT_SIZE / 2.0 - 2.0,
+            );
+            canvas.draw_str(label, origin, &font, &paint);
+        }
+    }
+
+    fn is_prompt(&self) -> bool {
+        matches!(self.dialog.kind, DialogKind::Prompt(_))
+    }
+
+    fn response_for(&self, button: Button) -> DialogResponse {
+        match (button, &self.dialog.kind) {
+            (Button::Cancel, _) => DialogResponse::Cancel,
+            (Button::Ok, DialogKind::Prompt(_)) => DialogResponse::Text(self.input.clone()),
+            (Button::Ok, _) => DialogResponse::Ok,
+        }
+    }
+
+    /// Returns the dialog's buttons with their bounds, from right to left.
+    fn buttons(&self) -> Vec<(Button, Rect)> {
+        let buttons: &[Button] = match self.dialog.kind {
+            DialogKind::Alert => &[Button::Ok],
+            DialogKind::Confirm | DialogKind::Prompt(_) => &[Button::Ok, Button::Cancel],
+        };
+        let top = DIALOG_SIZE.height - PADDING - BUTTON_SIZE.height;
+        let mut right = DIALOG_SIZE.width - PADDING;
+        buttons
+            .iter()
+            .map(|&button| {
+                let rect = Rect::from_xywh(
+                    right - BUTTON_SIZE.width,
+                    top,
+                    BUTTON_SIZE.width,
+                    BUTTON_SIZE.height,
+                );
+                right -= BUTTON_SIZE.width + PADDING / 2.0;
+                (button, rect)
+            })
+            .collect()
+    }
+
+    fn cursor_position_as_point(&self) -> skia_safe::Point {
+        (self.cursor_position.x, self.cursor_position.y).into()
+    }
+}
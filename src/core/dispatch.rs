@@ -0,0 +1,844 @@
+use crate::core::dialog::DialogState;
+use crate::core::drag::{self, Drag, AUTO_SCROLL_INTERVAL};
+use crate::core::executor::Executor;
+use crate::core::hot_reload::HotReloader;
+use crate::core::session::Session;
+use crate::core::window::Window;
+use crate::core::*;
+use crate::renderer;
+use crate::ui::{self, Store};
+
+use skia_safe::{Color4f, Point};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+use winit::dpi::LogicalPosition;
+use winit::event::{ElementState, MouseButton};
+use winit::keyboard::{Key, NamedKey};
+use winit::window::{CursorGrabMode, CursorIcon, Fullscreen, WindowAttributes, WindowId};
+
+/// Path to the example UI description. Can be safely removed in an actual application.
+const EXAMPLE_UI_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/ui/example.ron");
+
+/// A handler of the actions triggered by the windows' widgets.
+pub(super) type ActionHandler = Box<dyn FnMut(&mut Window, &str, &Store)>;
+
+/// A handler of the changes of the windows' states.
+pub(super) type WindowChangeHandler = Box<dyn FnMut(&mut Window, &WindowChange, &Store)>;
+
+/// A handler of the files dropped on the windows' drop targets.
+pub(super) type FileDropHandler = Box<dyn FnMut(&mut Window, &FileDrop, &Store)>;
+
+/// A handler of the payloads dragged between the windows' widgets and dropped on their drop targets.
+pub(super) type DragDropHandler = Box<dyn FnMut(&mut Window, &DragDrop, &Store)>;
+
+/// A handler of the gestures recognized by the windows' widgets.
+pub(super) type GestureHandler = Box<dyn FnMut(&mut Window, &Gesture, &Store)>;
+
+/// A handler of the inputs received by the windows.
+pub(super) type InputHandler = Box<dyn FnMut(&mut Window, &Input, &Store)>;
+
+/// What the [`Dispatcher`] needs from the platform it runs on: the event loop of the
+/// [`Application`], or nothing but the CPU in a [`Harness`].
+pub(super) trait Platform {
+    /// Creates a window with the given attributes.
+    fn create_window(&mut self, attrs: WindowAttributes) -> Window;
+
+    /// Stops running once the last window has been closed.
+    fn exit(&mut self);
+}
+
+/// Dispatches the inputs of the windows to their widget trees, dialogs and handlers,
+/// and manages the windows, the same way in the [`Application`] and in a [`Harness`].
+pub(super) struct Dispatcher {
+    pub(super) windows: HashMap<WindowId, Window>,
+    pub(super) window_order: Vec<WindowId>,
+    dialogs: HashMap<WindowId, DialogState>,
+    hot_reloader: Option<HotReloader>,
+    pub(super) executor: Executor,
+    pub(super) scheduler: Scheduler,
+    pub(super) store: Store,
+    pub(super) action_handler: Option<ActionHandler>,
+    pub(super) window_change_handler: Option<WindowChangeHandler>,
+    pub(super) input_handler: Option<InputHandler>,
+    pub(super) file_drop_handler: Option<FileDropHandler>,
+    pub(super) drag_drop_handler: Option<DragDropHandler>,
+    pub(super) gesture_handler: Option<GestureHandler>,
+    pub(super) session_store: Option<SessionStore>,
+    pub(super) clipboard: Clipboard,
+    /// The actions handled so far, if they are being logged.
+    pub(super) action_log: Option<Vec<String>>,
+    drag: Option<Drag>,
+
+    // Stuff only for the example dialogs, UI and animation. Can be safely removed in an actual application.
+    close_confirmations: Vec<(WindowId, DialogHandle)>,
+    clicks: Rc<Cell<u32>>,
+    clicks_stopped: Debounced,
+}
+
+impl Dispatcher {
+    /// Creates a dispatcher without windows, running tasks on the `executor`
+    /// and reloading UI files with the `hot_reloader`, if there is one.
+    pub(super) fn new(
+        executor: Executor,
+        hot_reloader: Option<HotReloader>,
+        clipboard: Clipboard,
+    ) -> Self {
+        let scheduler = Scheduler::new();
+
+        // Stuff only for the example UI. Can be safely removed in an actual application.
+        let store = Store::new();
+        store.set("clicks", "Not clicked yet");
+        store.set(
+            "gesture",
+            "Tap, pan, pinch or rotate here, or press \"g\" to emulate touches",
+        );
+        let clicks = Rc::new(Cell::new(0));
+        let clicks_stopped = {
+            let (store, clicks) = (store.clone(), clicks.clone());
+            scheduler.debounce(Duration::from_secs(1), move || {
+                store.set(
+                    "clicks",
+                    format!("Clicked {} times in a row", clicks.take()),
+                );
+            })
+        };
+
+        Dispatcher {
+            windows: HashMap::new(),
+            window_order: Vec::new(),
+            dialogs: HashMap::new(),
+            hot_reloader,
+            executor,
+            scheduler,
+            store,
+            action_handler: None,
+            window_change_handler: None,
+            input_handler: None,
+            file_drop_handler: None,
+            drag_drop_handler: None,
+            gesture_handler: None,
+            session_store: None,
+            clipboard,
+            action_log: None,
+            drag: None,
+
+            // Stuff only for the example dialogs, UI and animation. Can be safely removed in an actual application.
+            close_confirmations: Vec::new(),
+            clicks,
+            clicks_stopped,
+        }
+    }
+
+    /// Opens a new window.
+    fn open_window(&mut self, platform: &mut dyn Platform, title: &str) {
+        let window = platform.create_window(Window::default_attrs().with_title(title));
+        self.add_window(window);
+    }
+
+    /// Starts managing a newly created `window` and requests it to be drawn for the first time.
+    pub(super) fn add_window(&mut self, mut window: Window) {
+        window.set_clipboard(self.clipboard.clone());
+
+        // Stuff only for rendering the example animation. Can be safely removed in an actual application.
+        window.set_target_fps(Some(60));
+
+        // Stuff only for the example title bar, used when decorations are hidden. Can be safely removed in an actual application.
+        window.set_resize_border(6.0);
+
+        window.request_redraw();
+        self.window_order.push(window.id());
+        self.windows.insert(window.id(), window);
+    }
+
+    /// Restores the windows and state saved by the [`SessionStore`], if there is one.
+    ///
+    /// The first saved window is restored to the window opened on start, and the others are opened.
+    pub(super) fn restore_session(&mut self, platform: &mut dyn Platform) {
+        let session = match &self.session_store {
+            Some(session_store) => session_store.load(),
+            None => return,
+        };
+        let Session { windows, state } = match session {
+            Some(session) => session,
+            None => return,
+        };
+        for (key, value) in state {
+            self.store.set(&key, value);
+        }
+        for (i, geometry) in windows.iter().enumerate() {
+            if i > 0 {
+                self.open_window(platform, &format!("Window {i}"));
+            }
+            if let Some(window) = self
+                .window_order
+                .last()
+                .and_then(|id| self.windows.get_mut(id))
+            {
+                window.restore_geometry(geometry);
+            }
+        }
+    }
+
+    /// Saves the windows that stay open after the window with the given `window_id` is closed,
+    /// or that window itself if it is the last one, if there is a [`SessionStore`].
+    fn save_session(&self, window_id: WindowId) {
+        let session_store = match &self.session_store {
+            Some(session_store) => session_store,
+            None => return,
+        };
+        if !self.windows.contains_key(&window_id) || self.dialogs.contains_key(&window_id) {
+            return;
+        }
+        let open: Vec<_> = self
+            .window_order
+            .iter()
+            .filter(|id| !self.dialogs.contains_key(*id))
+            .filter_map(|id| self.windows.get_key_value(id))
+            .collect();
+        let is_last = open.len() == 1;
+        let session = Session {
+            windows: open
+                .into_iter()
+                .filter(|(id, _)| is_last || **id != window_id)
+                .map(|(_, window)| window.geometry())
+                .collect(),
+            state: session_store
+                .persisted_keys()
+                .iter()
+                .map(|key| {
+                    (
+                        key.clone(),
+                        self.store.signal(key).with_untracked(String::clone),
+                    )
+                })
+                .collect(),
+        };
+        session_store.save(&session);
+    }
+
+    /// Loads the widget tree of the window with the given `window_id` from the
+    /// [`UiDescription`](crate::ui::UiDescription) at `path`, binding it to the [`Store`].
+    ///
+    /// In debug builds, the description and all the files it depends on are watched,
+    /// and the widget tree is rebuilt whenever any of them changes.
+    pub(super) fn load_ui(&mut self, window_id: WindowId, path: impl Into<PathBuf>) {
+        let window = match self.windows.get_mut(&window_id) {
+            Some(window) => window,
+            None => return,
+        };
+        if let Err(e) = window.load_ui(path, &self.store) {
+            eprintln!("{e}");
+        }
+    }
+
+    /// Watches the UI files of the windows whose widget trees have been replaced,
+    /// and rebuilds the widget trees of the windows whose UI files have changed.
+    pub(super) fn reload_changed_uis(&mut self) {
+        let hot_reloader = match &mut self.hot_reloader {
+            Some(hot_reloader) => hot_reloader,
+            None => return,
+        };
+        for (&window_id, window) in &mut self.windows {
+            if window.take_ui_replaced() {
+                hot_reloader.watch(window_id, window.ui_dependencies().to_vec());
+            }
+        }
+        for window_id in hot_reloader.changed_windows() {
+            if let Some(window) = self.windows.get_mut(&window_id) {
+                if let Err(e) = window.reload_ui() {
+                    eprintln!("{e}");
+                }
+                hot_reloader.watch(window_id, window.ui_dependencies().to_vec());
+            }
+        }
+    }
+
+    /// Passes the changes of the windows' states to the window change handler.
+    pub(super) fn notify_window_changes(&mut self) {
+        for window in self.windows.values_mut() {
+            let changes = window.take_changes();
+            if let Some(handler) = &mut self.window_change_handler {
+                for change in &changes {
+                    handler(window, change, &self.store);
+                }
+            }
+        }
+    }
+
+    /// Redraws the windows whose widgets depend on [`Signal`](ui::Signal)s that have changed.
+    pub(super) fn redraw_invalidated_windows(&mut self) {
+        for (window_id, widgets) in ui::take_invalidated() {
+            if let Some(window) = self.windows.get_mut(&window_id) {
+                window.invalidate(&widgets);
+            }
+        }
+    }
+
+    /// Requests a redraw of the windows showing the example animation whose next frame is due,
+    /// and returns the earliest time a frame of the other animated windows is due, if any.
+    ///
+    /// Without a [target frame rate](Window::set_target_fps), frames are requested right away
+    /// and paced by vsync when they are presented. Can be safely removed in an actual application.
+    pub(super) fn animate(&mut self, now: Instant) -> Option<Instant> {
+        let animated = self
+            .windows
+            .iter()
+            .filter(|(id, window)| !window.has_ui() && !self.dialogs.contains_key(*id));
+        let mut next_frame: Option<Instant> = None;
+        for (_, window) in animated {
+            match window.next_frame_time() {
+                Some(time) if time > now => {
+                    next_frame = Some(next_frame.map_or(time, |next| next.min(time)));
+                }
+                _ => window.request_redraw(),
+            }
+        }
+        next_frame
+    }
+
+    /// Draws the window with the given `window_id`: its dialog, its widget tree or the example animation.
+    pub(super) fn redraw(&mut self, window_id: WindowId) {
+        let window = match self.windows.get_mut(&window_id) {
+            Some(window) => window,
+            None => return,
+        };
+        if let Some(dialog) = self.dialogs.get(&window_id) {
+            window.reset_canvas(window.background());
+            window.draw(|context| dialog.draw(context.canvas()));
+        } else if window.has_ui() {
+            window.draw_ui();
+        } else {
+            // Stuff only for rendering the example animation. Can be safely removed in an actual application.
+            window.frame += 1;
+            let frame = window.frame;
+            window.reset_canvas(window.background());
+            window.draw(|context| {
+                renderer::render_frame(frame % 360, 60, 60, context);
+            });
+        }
+    }
+
+    /// Opens a modal `dialog` on top of the `parent` window.
+    ///
+    /// While the dialog is open, all input to the parent window is blocked.
+    /// The returned handle resolves to the user's choice once the dialog is closed.
+    fn open_dialog(
+        &mut self,
+        platform: &mut dyn Platform,
+        parent: WindowId,
+        dialog: Dialog,
+    ) -> DialogHandle {
+        let (state, handle) = DialogState::new(dialog, parent);
+        self.add_dialog(platform, state);
+        handle
+    }
+
+    /// Opens the dialogs the windows' handlers [opened](Window::open_dialog) on top of them.
+    pub(super) fn open_requested_dialogs(&mut self, platform: &mut dyn Platform) {
+        let requested: Vec<_> = self
+            .windows
+            .values_mut()
+            .flat_map(Window::take_requested_dialogs)
+            .collect();
+        for state in requested {
+            self.add_dialog(platform, state);
+        }
+    }
+
+    /// Creates the window of a dialog on top of its parent window.
+    fn add_dialog(&mut self, platform: &mut dyn Platform, state: DialogState) {
+        let attrs = match self.windows.get(&state.parent()) {
+            Some(parent) => state.window_attrs(
+                parent.outer_position(),
+                parent.outer_size(),
+                parent.scale_factor(),
+            ),
+            None => state.window_attrs(None, Default::default(), 1.0),
+        };
+        let window = platform.create_window(attrs);
+        self.dialogs.insert(window.id(), state);
+        self.add_window(window);
+    }
+
+    /// Closes the window with the given `window_id`, exiting if it was the last one.
+    ///
+    /// If the window is a dialog that has not been answered yet, it resolves to [`DialogResponse::Cancel`].
+    pub(super) fn close_window(&mut self, platform: &mut dyn Platform, window_id: WindowId) {
+        let dragging = self.drag.as_ref().is_some_and(|drag| {
+            drag.source == window_id || drag.hovered.is_some_and(|(id, _)| id == window_id)
+        });
+        if dragging {
+            self.end_drag(false);
+        }
+        self.save_session(window_id);
+        if self.windows.remove(&window_id).is_none() {
+            return;
+        }
+        if let Some(dialog) = self.dialogs.remove(&window_id) {
+            dialog.respond(DialogResponse::Cancel);
+            if let Some(parent) = self.windows.get(&dialog.parent()) {
+                parent.focus();
+            }
+        }
+        if let Some(hot_reloader) = &mut self.hot_reloader {
+            hot_reloader.unwatch(window_id);
+        }
+        if self.windows.is_empty() {
+            platform.exit();
+        }
+    }
+
+    /// Handles a request to close the window with the given `window_id`, e.g. from its close button.
+    ///
+    /// Windows blocked by a dialog stay open, and dialogs are cancelled.
+    pub(super) fn close_requested(&mut self, platform: &mut dyn Platform, window_id: WindowId) {
+        if self.focus_modal_dialog(window_id) {
+            return;
+        }
+        match self.dialogs.contains_key(&window_id) {
+            true => self.answer_dialog(platform, window_id, DialogResponse::Cancel),
+            false => self.close_window(platform, window_id),
+        }
+    }
+
+    /// Handles an `action` triggered by a widget in the window with the given `window_id`.
+    // Stuff only for the example UI. Should be replaced with the actions of an actual application.
+    fn handle_action(&mut self, platform: &mut dyn Platform, window_id: WindowId, action: &str) {
+        if let Some(action_log) = &mut self.action_log {
+            action_log.push(action.to_string());
+        }
+        match action {
+            "open_window" => {
+                let title = format!("Window {}", self.windows.len());
+                self.open_window(platform, title.as_str());
+            }
+            "close_window" => self.close_window(platform, window_id),
+            "minimize_window" => {
+                if let Some(window) = self.windows.get_mut(&window_id) {
+                    window.set_minimized(true);
+                }
+            }
+            "fit_canvas" => {
+                if let Some(window) = self.windows.get_mut(&window_id) {
+                    if let Some(canvas) = window.ui_mut().and_then(|ui| ui.find_mut("canvas")) {
+                        canvas.zoom_to_fit();
+                    }
+                    window.request_redraw();
+                }
+            }
+            "toggle_maximized" => {
+                if let Some(window) = self.windows.get_mut(&window_id) {
+                    let maximized = window.is_maximized();
+                    window.set_maximized(!maximized);
+                }
+            }
+            "confirm_close" => {
+                let dialog = Dialog::confirm("Close window", "Do you want to close this window?");
+                let handle = self.open_dialog(platform, window_id, dialog);
+                self.close_confirmations.push((window_id, handle));
+            }
+            "increment" => {
+                self.clicks.set(self.clicks.get() + 1);
+                let text = format!("Clicked {} times", self.clicks.get());
+                self.store.set("clicks", text);
+                self.clicks_stopped.trigger();
+            }
+            "copy_clicks" => {
+                let text = format!("Clicked {} times", self.clicks.get());
+                self.clipboard.set_text(text);
+            }
+            "count_primes" => {
+                let store = self.store.clone();
+                let spawner = self.executor.spawner();
+                store.set("primes", "Counting primes...");
+                self.executor.spawner().spawn(async move {
+                    let count = spawner
+                        .background(|| (2..2_000_000).filter(|&n| is_prime(n)).count())
+                        .await;
+                    store.set(
+                        "primes",
+                        format!("There are {count} primes below 2 000 000"),
+                    );
+                });
+            }
+            "remove_ui" => {
+                if let Some(window) = self.windows.get_mut(&window_id) {
+                    window.set_ui(None);
+                }
+            }
+            _ => match (self.windows.get_mut(&window_id), &mut self.action_handler) {
+                (Some(window), Some(handler)) => handler(window, action, &self.store),
+                _ => eprintln!("Unknown action: {action}"),
+            },
+        }
+    }
+
+    /// Returns the ID of the modal dialog blocking the window with the given `window_id`, if any.
+    pub(super) fn modal_dialog_of(&self, window_id: WindowId) -> Option<WindowId> {
+        self.dialogs
+            .iter()
+            .find(|(_, dialog)| dialog.parent() == window_id)
+            .map(|(&dialog_id, _)| dialog_id)
+    }
+
+    /// Brings the modal dialog blocking the window with the given `window_id` to the front, if there is one.
+    ///
+    /// Returns `true` if the window is blocked.
+    pub(super) fn focus_modal_dialog(&self, window_id: WindowId) -> bool {
+        match self.modal_dialog_of(window_id) {
+            Some(dialog_id) => {
+                self.windows[&dialog_id].focus();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Handles an `input` received by a dialog window.
+    ///
+    /// Returns `true` if the input was consumed by the dialog.
+    fn dialog_input(
+        &mut self,
+        platform: &mut dyn Platform,
+        window_id: WindowId,
+        input: &Input,
+    ) -> bool {
+        let (dialog, window) = match (
+            self.dialogs.get_mut(&window_id),
+            self.windows.get(&window_id),
+        ) {
+            (Some(dialog), Some(window)) => (dialog, window),
+            _ => return false,
+        };
+        let response = match input {
+            Input::Key {
+                key, state, text, ..
+            } => {
+                window.request_redraw();
+                dialog.handle_key(key, *state, text.as_deref())
+            }
+            Input::CursorMoved(position) => {
+                dialog.set_cursor_position(*position);
+                window.request_redraw();
+                None
+            }
+            Input::MouseButton {
+                button: MouseButton::Left,
+                state: ElementState::Released,
+            } => dialog.handle_click(),
+            _ => return false,
+        };
+        if let Some(response) = response {
+            self.answer_dialog(platform, window_id, response);
+        }
+        true
+    }
+
+    /// Resolves the dialog with the given `window_id` with the user's `response` and closes it.
+    fn answer_dialog(
+        &mut self,
+        platform: &mut dyn Platform,
+        window_id: WindowId,
+        response: DialogResponse,
+    ) {
+        if let Some(dialog) = self.dialogs.get(&window_id) {
+            dialog.respond(response);
+        }
+        self.close_window(platform, window_id);
+
+        // Stuff only for the example dialogs. Can be safely removed in an actual application.
+        let confirmed: Vec<_> = self
+            .close_confirmations
+            .iter()
+            .filter(|(_, handle)| handle.try_response() == Some(DialogResponse::Ok))
+            .map(|&(id, _)| id)
+            .collect();
+        self.close_confirmations
+            .retain(|(_, handle)| handle.try_response().is_none());
+        for id in confirmed {
+            self.close_window(platform, id);
+        }
+    }
+
+    /// Moves, drops or cancels the drag started in the window with the given ID with an `input` it received.
+    ///
+    /// The window keeps receiving cursor events while the button is pressed, even outside of it.
+    fn drag_input(&mut self, window_id: WindowId, input: &Input) {
+        if self.drag.as_ref().map(|drag| drag.source) != Some(window_id) {
+            return;
+        }
+        match input {
+            Input::CursorMoved(position) => self.move_drag(Point::new(position.x, position.y)),
+            Input::MouseButton {
+                button: MouseButton::Left,
+                state: ElementState::Released,
+            } => self.end_drag(true),
+            Input::Key {
+                key: Key::Named(NamedKey::Escape),
+                state: ElementState::Pressed,
+                ..
+            } => self.end_drag(false),
+            _ => {}
+        }
+    }
+
+    /// Moves the drag to a `position` in its source window, in logical pixels, finding the window under
+    /// the cursor and highlighting its drop target, if it accepts the payload.
+    ///
+    /// The cursor can only be followed into other windows where the platform reports where windows are.
+    fn move_drag(&mut self, position: Point) {
+        let drag = match &mut self.drag {
+            Some(drag) => drag,
+            None => return,
+        };
+        let source = match self.windows.get(&drag.source) {
+            Some(source) => source,
+            None => return,
+        };
+        let size = source.logical_size();
+        let in_source =
+            (0.0..size.width).contains(&position.x) && (0.0..size.height).contains(&position.y);
+        let hovered = match in_source {
+            true => Some((drag.source, position)),
+            false => source.window_to_desktop(position).and_then(|desktop| {
+                self.windows
+                    .iter()
+                    .filter(|(id, _)| **id != drag.source)
+                    .find_map(|(id, window)| Some((*id, window.desktop_to_window(desktop)?)))
+            }),
+        };
+
+        let previous = drag.hovered.map(|(id, _)| id);
+        for (id, window) in &mut self.windows {
+            let position = hovered.filter(|(hovered, _)| hovered == id).map(|(_, p)| p);
+            if position.is_none() && previous != Some(*id) {
+                continue;
+            }
+            window.drag_over(position, &drag.payload);
+            let preview = position.zip(drag.preview.clone());
+            window.set_drag_preview(preview.map(|(position, image)| (image, position - drag.grab)));
+        }
+        drag.auto_scroll = hovered
+            .map(|(id, position)| {
+                let size = self.windows[&id].logical_size();
+                drag::auto_scroll(position, (size.width, size.height))
+            })
+            .unwrap_or_default();
+        drag.hovered = hovered;
+    }
+
+    /// Ends the drag, dropping its payload on the drop target under the cursor if `drop` is `true`.
+    fn end_drag(&mut self, drop: bool) {
+        let drag = match self.drag.take() {
+            Some(drag) => drag,
+            None => return,
+        };
+        let (window_id, position) = match drag.hovered {
+            Some(hovered) => hovered,
+            None => return,
+        };
+        let window = match self.windows.get_mut(&window_id) {
+            Some(window) => window,
+            None => return,
+        };
+        let target = window.drag_over(Some(position), &drag.payload);
+        window.drag_over(None, &drag.payload);
+        window.set_drag_preview(None);
+        if let (true, Some((action, target_id)), Some(handler)) =
+            (drop, target, &mut self.drag_drop_handler)
+        {
+            let dropped = DragDrop {
+                action,
+                target_id,
+                payload: drag.payload,
+                source: drag.source,
+                position: LogicalPosition::new(position.x, position.y),
+            };
+            handler(window, &dropped, &self.store);
+        }
+    }
+
+    /// Scrolls the viewport under a dragged payload while the cursor is near the edges of its window,
+    /// and returns when the next step is due, if any.
+    pub(super) fn auto_scroll(&mut self, now: Instant) -> Option<Instant> {
+        let drag = self.drag.as_mut()?;
+        let (window_id, position) = drag.hovered?;
+        if drag.auto_scroll.is_zero() {
+            return None;
+        }
+        if now >= drag.next_auto_scroll {
+            drag.next_auto_scroll = now + AUTO_SCROLL_INTERVAL;
+            if let Some(window) = self.windows.get_mut(&window_id) {
+                window.auto_scroll(position, drag.auto_scroll);
+            }
+        }
+        Some(drag.next_auto_scroll)
+    }
+
+    /// Recognizes the long presses of fingers held still until `now`, and returns when the next one is due.
+    pub(super) fn recognize_long_presses(&mut self, now: Instant) -> Option<Instant> {
+        let mut deadline = None;
+        for window in self.windows.values_mut() {
+            if let Some(gesture) = window.poll_gestures(now) {
+                if let Some(handler) = &mut self.gesture_handler {
+                    handler(window, &gesture, &self.store);
+                }
+            }
+            deadline = deadline
+                .into_iter()
+                .chain(window.next_gesture_deadline())
+                .min();
+        }
+        deadline
+    }
+
+    /// Handles an `input` received by the window with the given ID at `now`.
+    ///
+    /// The user's input to windows blocked by a dialog brings the dialog to the front instead.
+    pub(super) fn input(
+        &mut self,
+        platform: &mut dyn Platform,
+        window_id: WindowId,
+        input: Input,
+        now: Instant,
+    ) {
+        if !self.windows.contains_key(&window_id) {
+            return;
+        }
+        if input.is_user_input() && self.focus_modal_dialog(window_id) {
+            return;
+        }
+        if self.dialog_input(platform, window_id, &input) {
+            return;
+        }
+        self.input_event(platform, window_id, input, now);
+        self.open_requested_dialogs(platform);
+    }
+
+    /// Dispatches an `input` to the widget tree and the handlers of the window with the given ID.
+    fn input_event(
+        &mut self,
+        platform: &mut dyn Platform,
+        window_id: WindowId,
+        input: Input,
+        now: Instant,
+    ) {
+        let window_count = self.windows.len();
+        self.drag_input(window_id, &input);
+        let window = match self.windows.get_mut(&window_id) {
+            Some(window) => window,
+            None => return,
+        };
+        if let Some(action) = window.handle_input(&input, now) {
+            self.handle_action(platform, window_id, &action);
+            return;
+        }
+        if let Some(drag) = window.take_started_drag() {
+            self.drag = Some(drag);
+            self.drag_input(window_id, &input);
+        }
+        let window = match self.windows.get_mut(&window_id) {
+            Some(window) => window,
+            None => return,
+        };
+        for drop in window.take_file_drops() {
+            if let Some(handler) = &mut self.file_drop_handler {
+                handler(window, &drop, &self.store);
+            }
+        }
+        for gesture in window.take_gestures() {
+            if let Some(handler) = &mut self.gesture_handler {
+                handler(window, &gesture, &self.store);
+            }
+        }
+        if let Some(handler) = &mut self.input_handler {
+            handler(window, &input, &self.store);
+        }
+
+        // Stuff only for the example keyboard shortcuts. Can be safely removed in an actual application.
+        // Keys typed into a text input only edit its text.
+        if window.is_editing_text() {
+            return;
+        }
+        if let Input::Key {
+            key,
+            state: ElementState::Released,
+            repeat: false,
+            ..
+        } = input
+        {
+            if key == "q" {
+                self.close_requested(platform, window_id);
+            } else if key == "a" {
+                let title = format!("Window {}", window_count);
+                self.open_window(platform, title.as_str());
+            } else if key == "c" {
+                self.handle_action(platform, window_id, "confirm_close");
+            } else if key == "u" {
+                self.load_ui(window_id, EXAMPLE_UI_PATH);
+            } else if key == Key::Named(NamedKey::F3) {
+                let visible = window.diagnostics_visible();
+                window.set_diagnostics_visible(!visible);
+            } else if key == "f" {
+                let fullscreen = match window.fullscreen() {
+                    Some(_) => None,
+                    None => Some(Fullscreen::Borderless(None)),
+                };
+                window.set_fullscreen(fullscreen);
+            } else if key == "m" {
+                let maximized = window.is_maximized();
+                window.set_maximized(!maximized);
+            } else if key == "n" {
+                window.set_minimized(true);
+            } else if key == "t" {
+                let always_on_top = window.is_always_on_top();
+                window.set_always_on_top(!always_on_top);
+            } else if key == "d" {
+                let decorations = window.has_decorations();
+                window.set_decorations(!decorations);
+            } else if key == "s" {
+                let monitors = window.monitors();
+                let current = window.current_monitor();
+                let index = monitors.iter().position(|m| Some(m) == current.as_ref());
+                let next = index.map_or(0, |index| (index + 1) % monitors.len());
+                if let Some(monitor) = monitors.get(next) {
+                    window.move_to_monitor(monitor);
+                }
+            } else if key == "o" {
+                let transparent = !window.is_transparent();
+                window.set_transparent(transparent);
+                window.set_blur(transparent);
+                window.set_background(match transparent {
+                    true => Color4f::new(1.0, 1.0, 1.0, 0.6),
+                    false => Color4f::new(1.0, 1.0, 1.0, 1.0),
+                });
+            } else if key == "l" {
+                let locked = window.cursor_grab() == CursorGrabMode::Locked;
+                window.set_cursor_grab(match locked {
+                    true => CursorGrabMode::None,
+                    false => CursorGrabMode::Locked,
+                });
+                window.set_cursor_visible(locked);
+            } else if key == "g" {
+                let emulation = window.touch_emulation();
+                window.set_touch_emulation(!emulation);
+            } else if key == "p" {
+                window.set_cursor(match window.cursor() {
+                    Cursor::Icon(CursorIcon::Crosshair) => CursorIcon::Default,
+                    _ => CursorIcon::Crosshair,
+                });
+            }
+        }
+    }
+}
+
+/// Checks whether `n` is a prime number. Can be safely removed in an actual application.
+fn is_prime(n: u32) -> bool {
+    n >= 2
+        && (2..)
+            .take_while(|d| d * d <= n)
+            .all(|d| !n.is_multiple_of(d))
+}
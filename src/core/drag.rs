@@ -0,0 +1,105 @@
+use crate::ui::{DragPayload, Widget};
+
+use skia_safe::{Image, Point, Rect, Vector};
+use std::time::{Duration, Instant};
+use winit::dpi::LogicalPosition;
+use winit::window::WindowId;
+
+/// Distance, in logical pixels, from the edges of a window within which dragging scrolls it.
+const AUTO_SCROLL_MARGIN: f32 = 32.0;
+
+/// Distance, in logical pixels, scrolled per step when dragging right at the edge of a window.
+const AUTO_SCROLL_STEP: f32 = 12.0;
+
+/// Time between two steps of auto-scrolling.
+pub(super) const AUTO_SCROLL_INTERVAL: Duration = Duration::from_millis(16);
+
+/// A [payload](DragPayload) dragged from a widget and dropped on a [`DropTarget`](crate::ui::DropTarget)
+/// of one of the application's windows.
+#[derive(Debug, Clone)]
+pub struct DragDrop {
+    /// The drop target's action.
+    pub action: String,
+    /// The ID of the drop target, if it has one.
+    pub target_id: Option<String>,
+    /// The dropped payload.
+    pub payload: DragPayload,
+    /// The window the payload was dragged from.
+    pub source: WindowId,
+    /// The position of the cursor in the window the payload was dropped on, in logical pixels.
+    pub position: LogicalPosition<f32>,
+}
+
+/// A widget that has started being dragged in a [`View`](crate::core::view::View).
+#[derive(Debug, Clone)]
+pub(super) struct DragStart {
+    /// A copy of the dragged widget, to render its preview.
+    pub(super) widget: Widget,
+    /// The bounds of the widget in the window, in logical pixels, which differ from its own
+    /// [bounds](Widget::bounds) inside viewports.
+    pub(super) bounds: Rect,
+    /// The position of the cursor relative to the widget's top-left corner, in logical pixels.
+    pub(super) grab: Vector,
+}
+
+/// A payload being dragged between the application's windows.
+pub(super) struct Drag {
+    pub(super) payload: DragPayload,
+    pub(super) source: WindowId,
+    /// An image of the dragged widget, drawn under the cursor.
+    pub(super) preview: Option<Image>,
+    /// The position of the cursor relative to the preview's top-left corner, in logical pixels.
+    pub(super) grab: Vector,
+    /// The window under the cursor and the position of the cursor in it, in logical pixels.
+    pub(super) hovered: Option<(WindowId, Point)>,
+    /// The distance to scroll the hovered window per step, if the cursor is near its edges.
+    pub(super) auto_scroll: Vector,
+    pub(super) next_auto_scroll: Instant,
+}
+
+/// Returns the distance to scroll per step while dragging at `position` in a window of the given `size`,
+/// faster the closer the cursor is to an edge, and towards it.
+pub(super) fn auto_scroll(position: Point, size: (f32, f32)) -> Vector {
+    let speed = |distance_to_start: f32, distance_to_end: f32| {
+        let towards = |distance: f32| (1.0 - distance / AUTO_SCROLL_MARGIN).clamp(0.0, 1.0);
+        (towards(distance_to_end) - towards(distance_to_start)) * AUTO_SCROLL_STEP
+    };
+    Vector::new(
+        speed(position.x, size.0 - position.x),
+        speed(position.y, size.1 - position.y),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: (f32, f32) = (400.0, 300.0);
+
+    #[test]
+    fn dragging_away_from_the_edges_doesnt_scroll() {
+        assert!(auto_scroll(Point::new(200.0, 150.0), SIZE).is_zero());
+        assert!(auto_scroll(Point::new(32.0, 268.0), SIZE).is_zero());
+    }
+
+    #[test]
+    fn dragging_near_an_edge_scrolls_towards_it() {
+        assert_eq!(
+            auto_scroll(Point::new(200.0, 300.0), SIZE),
+            Vector::new(0.0, 12.0)
+        );
+        assert_eq!(
+            auto_scroll(Point::new(0.0, 150.0), SIZE),
+            Vector::new(-12.0, 0.0)
+        );
+        assert_eq!(
+            auto_scroll(Point::new(16.0, 284.0), SIZE),
+            Vector::new(-6.0, 6.0)
+        );
+    }
+
+    #[test]
+    fn opposite_edges_of_small_windows_cancel_out() {
+        assert!(auto_scroll(Point::new(20.0, 20.0), (40.0, 40.0)).is_zero());
+    }
+}
@@ -0,0 +1,54 @@
+use skia_safe::{scalar, Canvas};
+use winit::dpi::{LogicalSize, PhysicalSize};
+
+/// The canvas of a frame being drawn, along with the coordinate space it is drawn in.
+///
+/// Drawing happens in logical pixels: the canvas is scaled by the window's scale factor once per frame,
+/// so the same drawing code looks the same on monitors of any density. Code that needs to draw
+/// exactly on physical pixels, such as hairlines or pixel-aligned images, can use
+/// [`with_device_pixels`](DrawContext::with_device_pixels).
+pub struct DrawContext<'a> {
+    canvas: &'a Canvas,
+    scale_factor: scalar,
+    size: LogicalSize<scalar>,
+}
+
+impl<'a> DrawContext<'a> {
+    pub(super) fn new(canvas: &'a Canvas, scale_factor: scalar, size: LogicalSize<scalar>) -> Self {
+        DrawContext {
+            canvas,
+            scale_factor,
+            size,
+        }
+    }
+
+    /// Returns the canvas, which maps one unit to one logical pixel from the top-left corner of the window.
+    pub fn canvas(&self) -> &'a Canvas {
+        self.canvas
+    }
+
+    /// Returns the scale factor used to map logical pixels to physical pixels.
+    pub fn scale_factor(&self) -> scalar {
+        self.scale_factor
+    }
+
+    /// Returns the size of the drawing area in logical pixels.
+    pub fn size(&self) -> LogicalSize<scalar> {
+        self.size
+    }
+
+    /// Returns the size of the drawing area in physical pixels.
+    pub fn physical_size(&self) -> PhysicalSize<u32> {
+        self.size.to_physical(self.scale_factor as f64)
+    }
+
+    /// Calls `drawing` with the canvas mapping one unit to one physical pixel from the top-left corner
+    /// of the window, and restores the logical-pixel transform afterwards. The clip is kept.
+    pub fn with_device_pixels<R>(&self, drawing: impl FnOnce(&Canvas) -> R) -> R {
+        self.canvas.save();
+        self.canvas.reset_matrix();
+        let result = drawing(self.canvas);
+        self.canvas.restore();
+        result
+    }
+}
@@ -0,0 +1,180 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+
+/// A future spawned on the event loop thread.
+type LocalTask = Pin<Box<dyn Future<Output = ()>>>;
+
+/// A handle for spawning futures onto the application's event loop thread.
+///
+/// Spawned futures are polled between events, so they can update [`Signal`](crate::ui::Signal)s
+/// and other state owned by the application. Blocking work should be moved off the thread
+/// with [`background`](Spawner::background).
+///
+/// Spawners are cheap to clone; all clones spawn onto the same executor.
+#[derive(Clone)]
+pub struct Spawner {
+    spawned: Rc<RefCell<Vec<LocalTask>>>,
+    #[cfg(feature = "tokio")]
+    runtime: tokio::runtime::Handle,
+}
+
+/// A lightweight executor running futures on the event loop thread.
+///
+/// Woken tasks are recorded and the event loop is woken up,
+/// so that they are polled the next time [`run_ready`](Executor::run_ready) is called.
+pub(super) struct Executor {
+    tasks: HashMap<u64, LocalTask>,
+    next_id: u64,
+    spawned: Rc<RefCell<Vec<LocalTask>>>,
+    ready: Arc<Mutex<HashSet<u64>>>,
+    wake: Arc<dyn Fn() + Send + Sync>,
+    #[cfg(feature = "tokio")]
+    runtime: tokio::runtime::Runtime,
+}
+
+/// Wakes a single task of an [`Executor`].
+struct TaskWaker {
+    id: u64,
+    ready: Arc<Mutex<HashSet<u64>>>,
+    wake: Arc<dyn Fn() + Send + Sync>,
+}
+
+/// The result of a [background](Spawner::background) computation, shared with the thread computing it.
+struct Oneshot<R> {
+    result: Option<std::thread::Result<R>>,
+    waker: Option<Waker>,
+}
+
+impl Spawner {
+    /// Spawns a `future` onto the event loop thread.
+    pub fn spawn(&self, future: impl Future<Output = ()> + 'static) {
+        self.spawned.borrow_mut().push(Box::pin(future));
+    }
+
+    /// Runs `f` on a background thread and returns a future resolving to its result.
+    ///
+    /// If `f` panics, the panic is resumed when the future is polled.
+    pub fn background<R: Send + 'static>(
+        &self,
+        f: impl FnOnce() -> R + Send + 'static,
+    ) -> impl Future<Output = R> {
+        let shared = Arc::new(Mutex::new(Oneshot {
+            result: None,
+            waker: None,
+        }));
+        let sender = shared.clone();
+        let job = move || {
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f));
+            let mut shared = sender.lock().unwrap();
+            shared.result = Some(result);
+            if let Some(waker) = shared.waker.take() {
+                waker.wake();
+            }
+        };
+
+        #[cfg(feature = "tokio")]
+        self.runtime.spawn_blocking(job);
+        #[cfg(not(feature = "tokio"))]
+        std::thread::spawn(job);
+
+        std::future::poll_fn(move |cx| {
+            let mut shared = shared.lock().unwrap();
+            match shared.result.take() {
+                Some(Ok(result)) => Poll::Ready(result),
+                Some(Err(panic)) => std::panic::resume_unwind(panic),
+                None => {
+                    shared.waker = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        })
+    }
+}
+
+impl Executor {
+    /// Creates a new executor without any tasks.
+    ///
+    /// `wake` is called, possibly from other threads, whenever a task is woken.
+    pub(super) fn new(wake: impl Fn() + Send + Sync + 'static) -> Self {
+        Executor {
+            tasks: HashMap::new(),
+            next_id: 0,
+            spawned: Rc::default(),
+            ready: Arc::default(),
+            wake: Arc::new(wake),
+            #[cfg(feature = "tokio")]
+            runtime: tokio::runtime::Builder::new_multi_thread()
+                .enable_all()
+                .build()
+                .expect("Could not start the tokio runtime"),
+        }
+    }
+
+    /// Returns a new handle for spawning futures onto the executor.
+    pub(super) fn spawner(&self) -> Spawner {
+        Spawner {
+            spawned: self.spawned.clone(),
+            #[cfg(feature = "tokio")]
+            runtime: self.runtime.handle().clone(),
+        }
+    }
+
+    /// Polls the newly spawned tasks and the tasks woken since the last call.
+    pub(super) fn run_ready(&mut self) {
+        #[cfg(feature = "tokio")]
+        let _guard = self.runtime.enter();
+
+        let mut ready = std::mem::take(&mut *self.ready.lock().unwrap());
+        // Tasks spawned by other tasks are polled right away, as nothing else would wake them.
+        loop {
+            for task in self.spawned.take() {
+                self.tasks.insert(self.next_id, task);
+                ready.insert(self.next_id);
+                self.next_id += 1;
+            }
+            if ready.is_empty() {
+                return;
+            }
+            for id in ready.drain() {
+                self.poll(id);
+            }
+        }
+    }
+
+    /// Polls the task with the given `id`, removing it if it has finished.
+    fn poll(&mut self, id: u64) {
+        let task = match self.tasks.get_mut(&id) {
+            Some(task) => task,
+            None => return,
+        };
+        let waker = Waker::from(Arc::new(TaskWaker {
+            id,
+            ready: self.ready.clone(),
+            wake: self.wake.clone(),
+        }));
+        if task
+            .as_mut()
+            .poll(&mut Context::from_waker(&waker))
+            .is_ready()
+        {
+            self.tasks.remove(&id);
+        }
+    }
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        if self.ready.lock().unwrap().insert(self.id) {
+            (self.wake)();
+        }
+    }
+}
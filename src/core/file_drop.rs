@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+use winit::dpi::LogicalPosition;
+
+/// Files dropped from another application on a widget with a [`DropTarget`](crate::ui::DropTarget).
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileDrop {
+    /// The drop target's action.
+    pub action: String,
+    /// The dropped files the drop target accepts, in the order they were dropped.
+    pub paths: Vec<PathBuf>,
+    /// The position of the cursor when the files were dropped, in logical pixels.
+    pub position: LogicalPosition<f32>,
+}
+
+/// The files being dragged over a window.
+///
+/// The OS reports each file of a drag separately, first when the drag enters the window and again
+/// when the files are dropped, so a drop is complete once all the hovering files have been dropped.
+#[derive(Debug, Default)]
+pub(super) struct FileDrag {
+    hovered: Vec<PathBuf>,
+    dropped: Vec<PathBuf>,
+}
+
+impl FileDrag {
+    /// Returns the files hovering over the window.
+    pub(super) fn hovered(&self) -> &[PathBuf] {
+        &self.hovered
+    }
+
+    /// Adds a file to the ones hovering over the window.
+    pub(super) fn hover(&mut self, path: PathBuf) {
+        self.hovered.push(path);
+    }
+
+    /// Forgets the hovering files, after they have left the window.
+    pub(super) fn cancel(&mut self) {
+        self.hovered.clear();
+        self.dropped.clear();
+    }
+
+    /// Adds a dropped file, and returns all the dropped files once the drop is complete.
+    pub(super) fn drop_file(&mut self, path: PathBuf) -> Option<Vec<PathBuf>> {
+        self.dropped.push(path);
+        if self.dropped.len() < self.hovered.len() {
+            return None;
+        }
+        self.hovered.clear();
+        Some(std::mem::take(&mut self.dropped))
+    }
+}
@@ -0,0 +1,117 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Weight of the newest sample in the moving averages.
+const SMOOTHING: f64 = 0.1;
+/// Intervals between frames longer than this are not counted,
+/// so that idle periods do not skew the averages.
+const MAX_FRAME_INTERVAL: Duration = Duration::from_millis(250);
+/// Number of frame intervals kept in the history.
+const HISTORY_LENGTH: usize = 120;
+
+/// Timing statistics of the frames presented by a window.
+#[derive(Debug, Clone, Default)]
+pub struct FrameStats {
+    frame_count: u64,
+    last_present: Option<Instant>,
+    last_frame_time: Duration,
+    last_draw_time: Duration,
+    last_flush_time: Duration,
+    average_frame_time: Duration,
+    average_frame_interval: Option<Duration>,
+    history: VecDeque<Duration>,
+}
+
+/// Points in time at which the stages of drawing a frame finished.
+pub(super) struct FrameTiming {
+    /// Drawing has started.
+    pub start: Instant,
+    /// All the drawing commands have been recorded.
+    pub drawn: Instant,
+    /// The drawing commands have been flushed and submitted to the GPU.
+    pub flushed: Instant,
+    /// The frame has been presented.
+    pub presented: Instant,
+}
+
+impl FrameStats {
+    /// Returns the number of frames presented so far.
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    /// Returns when the last frame was presented.
+    pub fn last_present(&self) -> Option<Instant> {
+        self.last_present
+    }
+
+    /// Returns how long it took to draw and present the last frame.
+    pub fn last_frame_time(&self) -> Duration {
+        self.last_frame_time
+    }
+
+    /// Returns the CPU time spent recording the drawing commands of the last frame.
+    pub fn last_draw_time(&self) -> Duration {
+        self.last_draw_time
+    }
+
+    /// Returns the time spent flushing and submitting the last frame to the GPU.
+    pub fn last_flush_time(&self) -> Duration {
+        self.last_flush_time
+    }
+
+    /// Returns the moving average of the time it takes to draw and present a frame.
+    pub fn average_frame_time(&self) -> Duration {
+        self.average_frame_time
+    }
+
+    /// Returns the moving average of the time between two consecutive frames,
+    /// if frames have been presented continuously.
+    pub fn average_frame_interval(&self) -> Option<Duration> {
+        self.average_frame_interval
+    }
+
+    /// Returns the number of frames presented per second while presenting continuously.
+    pub fn fps(&self) -> Option<f64> {
+        self.average_frame_interval
+            .map(|interval| 1.0 / interval.as_secs_f64())
+    }
+
+    /// Returns the intervals between the most recent consecutive frames, from the oldest to the newest.
+    pub fn frame_intervals(&self) -> impl ExactSizeIterator<Item = Duration> + '_ {
+        self.history.iter().copied()
+    }
+
+    /// Records a newly presented frame.
+    pub(super) fn record(&mut self, timing: FrameTiming) {
+        let frame_time = timing.presented - timing.start;
+        self.average_frame_time = if self.frame_count == 0 {
+            frame_time
+        } else {
+            smooth(self.average_frame_time, frame_time)
+        };
+        if let Some(last_present) = self.last_present {
+            let interval = timing.presented - last_present;
+            if interval <= MAX_FRAME_INTERVAL {
+                self.average_frame_interval = Some(match self.average_frame_interval {
+                    Some(average) => smooth(average, interval),
+                    None => interval,
+                });
+                if self.history.len() == HISTORY_LENGTH {
+                    self.history.pop_front();
+                }
+                self.history.push_back(interval);
+            }
+        }
+        self.frame_count += 1;
+        self.last_present = Some(timing.presented);
+        self.last_frame_time = frame_time;
+        self.last_draw_time = timing.drawn - timing.start;
+        self.last_flush_time = timing.flushed - timing.drawn;
+    }
+}
+
+/// Moves the `average` towards the new `sample`.
+fn smooth(average: Duration, sample: Duration) -> Duration {
+    average.mul_f64(1.0 - SMOOTHING) + sample.mul_f64(SMOOTHING)
+}
@@ -0,0 +1,550 @@
+use crate::ui::GestureKind;
+
+use skia_safe::{Point, Vector};
+use std::f32::consts::PI;
+use std::time::{Duration, Instant};
+use winit::dpi::LogicalPosition;
+use winit::event::TouchPhase;
+
+/// Distance, in logical pixels, a finger can move before it no longer taps or long presses.
+const TAP_SLOP: f32 = 10.0;
+
+/// Time a finger has to be held without moving to long press.
+const LONG_PRESS_TIME: Duration = Duration::from_millis(500);
+
+/// Longest time between two taps of a double tap.
+const DOUBLE_TAP_TIME: Duration = Duration::from_millis(300);
+
+/// Farthest distance, in logical pixels, between two taps of a double tap.
+const DOUBLE_TAP_DISTANCE: f32 = 24.0;
+
+/// Distance, in logical pixels, the fingers have to move together to start panning.
+const PAN_DISTANCE: f32 = 10.0;
+
+/// Relative change of the distance between the fingers needed to start pinching.
+const PINCH_THRESHOLD: f32 = 0.05;
+
+/// Angle, in radians, the fingers have to turn to start rotating.
+const ROTATE_THRESHOLD: f32 = 0.1;
+
+/// A gesture recognized by a [`GestureRecognizer`](crate::ui::GestureRecognizer) of a widget.
+///
+/// The translation, scale and rotation are measured from the start of the gesture,
+/// so they can be applied to the state the widget had then.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gesture {
+    /// The recognizer's action.
+    pub action: String,
+    /// The ID of the widget the gesture was started on, if it has one.
+    pub target_id: Option<String>,
+    /// The recognized gesture.
+    pub kind: GestureKind,
+    /// The progress of the gesture. Taps and double taps are only reported once they have [ended](GesturePhase::Ended).
+    pub phase: GesturePhase,
+    /// The center of the fingers, or the cursor's position for touchpad gestures, in logical pixels.
+    pub position: LogicalPosition<f32>,
+    /// The distance the fingers have moved together, in logical pixels.
+    pub translation: Vector,
+    /// The ratio between the current and the initial distance between the fingers.
+    pub scale: f32,
+    /// The angle the fingers have turned, in radians, clockwise.
+    pub rotation: f32,
+}
+
+/// The progress of a [`Gesture`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GesturePhase {
+    /// The gesture has been recognized.
+    Started,
+    /// The fingers have moved since the gesture was last reported.
+    Changed,
+    /// The fingers have left the screen.
+    Ended,
+    /// The OS has stopped tracking the fingers, e.g. because another window has taken over.
+    Cancelled,
+}
+
+/// A finger touching a window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TouchPoint {
+    /// The ID of the finger, unique while it touches the screen.
+    pub id: u64,
+    /// The position of the finger, in logical pixels.
+    pub position: LogicalPosition<f32>,
+    /// The position where the finger started touching the window, in logical pixels.
+    pub start: LogicalPosition<f32>,
+}
+
+/// A gesture recognized by a [`GestureTracker`], before being matched to a widget's recognizers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct Recognized {
+    pub(super) kind: GestureKind,
+    pub(super) phase: GesturePhase,
+    pub(super) position: Point,
+}
+
+/// The center, average distance to the center and angle of the fingers at some point of a gesture.
+#[derive(Debug, Clone, Copy)]
+struct Shape {
+    center: Point,
+    spread: f32,
+    angle: Option<f32>,
+}
+
+/// Tracks the fingers touching a window, per ID, and recognizes the gestures they make.
+#[derive(Debug)]
+pub(super) struct GestureTracker {
+    touches: Vec<(u64, Point, Point)>,
+    started: Option<Instant>,
+    max_touches: usize,
+    moved: bool,
+    long_pressed: bool,
+    last_tap: Option<(Instant, Point)>,
+    /// The shape of the fingers when they last changed, and the transform accumulated until then.
+    reference: Option<Shape>,
+    translation: Vector,
+    scale: f32,
+    rotation: f32,
+    panning: bool,
+    pinching: bool,
+    rotating: bool,
+}
+
+impl GestureTracker {
+    /// Returns the fingers touching the window.
+    pub(super) fn touches(&self) -> Vec<TouchPoint> {
+        self.touches
+            .iter()
+            .map(|&(id, position, start)| TouchPoint {
+                id,
+                position: LogicalPosition::new(position.x, position.y),
+                start: LogicalPosition::new(start.x, start.y),
+            })
+            .collect()
+    }
+
+    /// Returns `true` if no finger touches the window.
+    pub(super) fn is_idle(&self) -> bool {
+        self.touches.is_empty()
+    }
+
+    /// Returns the distance the fingers have moved together since the gesture started.
+    pub(super) fn translation(&self) -> Vector {
+        self.translation + self.shape_change().0
+    }
+
+    /// Returns the ratio between the current and the initial distance between the fingers.
+    pub(super) fn scale(&self) -> f32 {
+        self.scale * self.shape_change().1
+    }
+
+    /// Returns the angle the fingers have turned since the gesture started, in radians.
+    pub(super) fn rotation(&self) -> f32 {
+        self.rotation + self.shape_change().2
+    }
+
+    /// Updates the tracker with a finger touching, moving on or leaving the window at `time`,
+    /// and returns the gestures this recognizes, changes or ends.
+    pub(super) fn touch(
+        &mut self,
+        id: u64,
+        phase: TouchPhase,
+        position: Point,
+        time: Instant,
+    ) -> Vec<Recognized> {
+        match phase {
+            TouchPhase::Started => {
+                if self.touches.is_empty() {
+                    self.reset();
+                    self.started = Some(time);
+                }
+                self.rebase(|touches| {
+                    touches.retain(|&(touch, _, _)| touch != id);
+                    touches.push((id, position, position));
+                });
+                self.max_touches = self.max_touches.max(self.touches.len());
+                Vec::new()
+            }
+            TouchPhase::Moved => {
+                match self.touches.iter_mut().find(|(touch, _, _)| *touch == id) {
+                    Some((_, current, start)) => {
+                        *current = position;
+                        if (position - *start).length() > TAP_SLOP {
+                            self.moved = true;
+                        }
+                    }
+                    None => return Vec::new(),
+                }
+                self.update_continuous()
+            }
+            TouchPhase::Ended => {
+                if !self.touches.iter().any(|&(touch, _, _)| touch == id) {
+                    return Vec::new();
+                }
+                let mut recognized = self.end_continuous(id, GesturePhase::Ended);
+                if self.touches.is_empty() {
+                    recognized.extend(self.tap(position, time));
+                }
+                recognized
+            }
+            TouchPhase::Cancelled => {
+                let mut recognized = Vec::new();
+                while let Some(&(id, _, _)) = self.touches.first() {
+                    recognized.extend(self.end_continuous(id, GesturePhase::Cancelled));
+                }
+                self.last_tap = None;
+                recognized
+            }
+        }
+    }
+
+    /// Recognizes a long press if a single finger has been held without moving until `time`.
+    pub(super) fn poll(&mut self, time: Instant) -> Option<Recognized> {
+        let deadline = self.long_press_deadline()?;
+        if time < deadline {
+            return None;
+        }
+        self.long_pressed = true;
+        Some(Recognized {
+            kind: GestureKind::LongPress,
+            phase: GesturePhase::Started,
+            position: self.touches[0].1,
+        })
+    }
+
+    /// Returns when a finger held without moving will be long pressing, if one is.
+    pub(super) fn long_press_deadline(&self) -> Option<Instant> {
+        if self.touches.len() != 1 || self.max_touches != 1 || self.moved || self.long_pressed {
+            return None;
+        }
+        self.started.map(|started| started + LONG_PRESS_TIME)
+    }
+
+    /// Updates the tracker with a gesture made on a touchpad at the cursor's `position`,
+    /// with a `translation`, a `scale` and a `rotation` relative to the last update.
+    pub(super) fn touchpad(
+        &mut self,
+        kind: GestureKind,
+        phase: TouchPhase,
+        position: Point,
+        translation: Vector,
+        scale: f32,
+        rotation: f32,
+    ) -> Recognized {
+        if phase == TouchPhase::Started {
+            match kind {
+                GestureKind::Pan => self.translation = Vector::default(),
+                GestureKind::Pinch => self.scale = 1.0,
+                GestureKind::Rotate => self.rotation = 0.0,
+                _ => {}
+            }
+        }
+        self.translation += translation;
+        // Touchpads may report a NaN scale, which would stick.
+        if scale.is_finite() {
+            self.scale *= scale;
+        }
+        self.rotation += rotation;
+        let phase = match phase {
+            TouchPhase::Started => GesturePhase::Started,
+            TouchPhase::Moved => GesturePhase::Changed,
+            TouchPhase::Ended => GesturePhase::Ended,
+            TouchPhase::Cancelled => GesturePhase::Cancelled,
+        };
+        Recognized {
+            kind,
+            phase,
+            position,
+        }
+    }
+
+    /// Forgets the previous gesture.
+    fn reset(&mut self) {
+        *self = GestureTracker {
+            last_tap: self.last_tap,
+            ..GestureTracker::default()
+        };
+    }
+
+    /// Changes the fingers with `change`, keeping the transform accumulated until then,
+    /// so that adding or removing a finger doesn't make the gesture jump.
+    fn rebase(&mut self, change: impl FnOnce(&mut Vec<(u64, Point, Point)>)) {
+        let (translation, scale, rotation) = self.shape_change();
+        self.translation += translation;
+        self.scale *= scale;
+        self.rotation += rotation;
+        change(&mut self.touches);
+        self.reference = self.shape();
+    }
+
+    /// Returns the current shape of the fingers, if any touch the window.
+    fn shape(&self) -> Option<Shape> {
+        if self.touches.is_empty() {
+            return None;
+        }
+        let count = self.touches.len() as f32;
+        let sum = self
+            .touches
+            .iter()
+            .fold(Vector::default(), |sum, &(_, position, _)| sum + position);
+        let center = Point::new(sum.x / count, sum.y / count);
+        let spread = self
+            .touches
+            .iter()
+            .map(|&(_, position, _)| (position - center).length())
+            .sum::<f32>()
+            / count;
+        let angle = match self.touches.as_slice() {
+            [(_, first, _), (_, second, _), ..] => {
+                let direction = *second - *first;
+                Some(direction.y.atan2(direction.x))
+            }
+            _ => None,
+        };
+        Some(Shape {
+            center,
+            spread,
+            angle,
+        })
+    }
+
+    /// Returns the translation, scale and rotation of the fingers since they last changed.
+    fn shape_change(&self) -> (Vector, f32, f32) {
+        let (reference, current) = match (self.reference, self.shape()) {
+            (Some(reference), Some(current)) => (reference, current),
+            _ => return (Vector::default(), 1.0, 0.0),
+        };
+        let scale = if reference.spread > 0.0 && current.spread > 0.0 {
+            current.spread / reference.spread
+        } else {
+            1.0
+        };
+        let rotation = match (reference.angle, current.angle) {
+            // The angle wraps around, but the fingers can't turn half a turn between two updates.
+            (Some(reference), Some(current)) => {
+                let turn = current - reference;
+                turn - (turn / (2.0 * PI)).round() * 2.0 * PI
+            }
+            _ => 0.0,
+        };
+        (current.center - reference.center, scale, rotation)
+    }
+
+    /// Starts or changes the pan, pinch and rotation once the fingers have moved far enough.
+    fn update_continuous(&mut self) -> Vec<Recognized> {
+        let position = match self.shape() {
+            Some(shape) => shape.center,
+            None => return Vec::new(),
+        };
+        let multi_touch = self.touches.len() > 1;
+        let gestures = [
+            (GestureKind::Pan, self.translation().length() > PAN_DISTANCE),
+            (
+                GestureKind::Pinch,
+                multi_touch && (self.scale() - 1.0).abs() > PINCH_THRESHOLD,
+            ),
+            (
+                GestureKind::Rotate,
+                multi_touch && self.rotation().abs() > ROTATE_THRESHOLD,
+            ),
+        ];
+        let mut recognized = Vec::new();
+        for (kind, threshold_reached) in gestures {
+            let active = match self.active(kind) {
+                Some(active) => active,
+                None => continue,
+            };
+            let phase = if *active {
+                GesturePhase::Changed
+            } else if threshold_reached {
+                *active = true;
+                GesturePhase::Started
+            } else {
+                continue;
+            };
+            recognized.push(Recognized {
+                kind,
+                phase,
+                position,
+            });
+        }
+        recognized
+    }
+
+    /// Removes the finger with the given `id`, and ends the gestures that need it with `phase`.
+    fn end_continuous(&mut self, id: u64, phase: GesturePhase) -> Vec<Recognized> {
+        let position = self.shape().map(|shape| shape.center).unwrap_or_default();
+        self.rebase(|touches| touches.retain(|&(touch, _, _)| touch != id));
+        let mut ended = match self.touches.len() {
+            0 => vec![GestureKind::LongPress, GestureKind::Pan],
+            _ => Vec::new(),
+        };
+        if self.touches.len() < 2 {
+            ended.extend([GestureKind::Pinch, GestureKind::Rotate]);
+        }
+        ended
+            .into_iter()
+            .filter(|&kind| self.active(kind).is_some_and(std::mem::take))
+            .map(|kind| Recognized {
+                kind,
+                phase,
+                position,
+            })
+            .collect()
+    }
+
+    /// Recognizes a tap, and a double tap with the previous one, when the last finger has left at `position`.
+    fn tap(&mut self, position: Point, time: Instant) -> Vec<Recognized> {
+        let quick = self
+            .started
+            .is_some_and(|started| time - started < LONG_PRESS_TIME);
+        if self.moved || self.long_pressed || self.max_touches != 1 || !quick {
+            self.last_tap = None;
+            return Vec::new();
+        }
+        let ended = |kind| Recognized {
+            kind,
+            phase: GesturePhase::Ended,
+            position,
+        };
+        let double_tap = self
+            .last_tap
+            .take()
+            .is_some_and(|(last_time, last_position)| {
+                time - last_time <= DOUBLE_TAP_TIME
+                    && (position - last_position).length() <= DOUBLE_TAP_DISTANCE
+            });
+        if double_tap {
+            vec![ended(GestureKind::Tap), ended(GestureKind::DoubleTap)]
+        } else {
+            self.last_tap = Some((time, position));
+            vec![ended(GestureKind::Tap)]
+        }
+    }
+
+    /// Returns whether a gesture of the given `kind` is in progress, to be updated.
+    /// Taps are recognized at once, so they are never in progress.
+    fn active(&mut self, kind: GestureKind) -> Option<&mut bool> {
+        match kind {
+            GestureKind::Pan => Some(&mut self.panning),
+            GestureKind::Pinch => Some(&mut self.pinching),
+            GestureKind::Rotate => Some(&mut self.rotating),
+            GestureKind::LongPress => Some(&mut self.long_pressed),
+            GestureKind::Tap | GestureKind::DoubleTap => None,
+        }
+    }
+}
+
+impl Default for GestureTracker {
+    fn default() -> Self {
+        GestureTracker {
+            touches: Vec::new(),
+            started: None,
+            max_touches: 0,
+            moved: false,
+            long_pressed: false,
+            last_tap: None,
+            reference: None,
+            translation: Vector::default(),
+            scale: 1.0,
+            rotation: 0.0,
+            panning: false,
+            pinching: false,
+            rotating: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(recognized: &[Recognized]) -> Vec<(GestureKind, GesturePhase)> {
+        recognized.iter().map(|r| (r.kind, r.phase)).collect()
+    }
+
+    #[test]
+    fn quick_touches_tap_and_double_tap() {
+        let mut tracker = GestureTracker::default();
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+
+        tracker.touch(1, TouchPhase::Started, Point::new(10.0, 10.0), at(0));
+        let first = tracker.touch(1, TouchPhase::Ended, Point::new(12.0, 11.0), at(100));
+        assert_eq!(kinds(&first), [(GestureKind::Tap, GesturePhase::Ended)]);
+        assert_eq!(first[0].position, Point::new(12.0, 11.0));
+
+        tracker.touch(2, TouchPhase::Started, Point::new(14.0, 10.0), at(200));
+        let second = tracker.touch(2, TouchPhase::Ended, Point::new(14.0, 10.0), at(250));
+        assert_eq!(
+            kinds(&second),
+            [
+                (GestureKind::Tap, GesturePhase::Ended),
+                (GestureKind::DoubleTap, GesturePhase::Ended)
+            ]
+        );
+    }
+
+    #[test]
+    fn moving_fingers_pan_instead_of_tapping() {
+        let mut tracker = GestureTracker::default();
+        let now = Instant::now();
+
+        tracker.touch(1, TouchPhase::Started, Point::new(10.0, 10.0), now);
+        let moved = tracker.touch(1, TouchPhase::Moved, Point::new(40.0, 10.0), now);
+        assert_eq!(kinds(&moved), [(GestureKind::Pan, GesturePhase::Started)]);
+        assert_eq!(tracker.translation(), Vector::new(30.0, 0.0));
+
+        let ended = tracker.touch(1, TouchPhase::Ended, Point::new(40.0, 10.0), now);
+        assert_eq!(kinds(&ended), [(GestureKind::Pan, GesturePhase::Ended)]);
+        assert!(tracker.is_idle());
+    }
+
+    #[test]
+    fn fingers_held_still_long_press() {
+        let mut tracker = GestureTracker::default();
+        let start = Instant::now();
+        let position = Point::new(50.0, 60.0);
+
+        tracker.touch(1, TouchPhase::Started, position, start);
+        let deadline = start + LONG_PRESS_TIME;
+        assert_eq!(tracker.long_press_deadline(), Some(deadline));
+        assert_eq!(tracker.poll(deadline - Duration::from_millis(1)), None);
+
+        let long_press = tracker.poll(deadline).unwrap();
+        assert_eq!(long_press.kind, GestureKind::LongPress);
+        assert_eq!(long_press.phase, GesturePhase::Started);
+        assert_eq!(long_press.position, position);
+        assert_eq!(tracker.long_press_deadline(), None);
+
+        let ended = tracker.touch(1, TouchPhase::Ended, position, deadline);
+        assert_eq!(
+            kinds(&ended),
+            [(GestureKind::LongPress, GesturePhase::Ended)]
+        );
+    }
+
+    #[test]
+    fn fingers_moving_apart_pinch() {
+        let mut tracker = GestureTracker::default();
+        let now = Instant::now();
+
+        tracker.touch(1, TouchPhase::Started, Point::new(100.0, 100.0), now);
+        tracker.touch(2, TouchPhase::Started, Point::new(200.0, 100.0), now);
+        assert_eq!(tracker.long_press_deadline(), None);
+
+        let moved = tracker.touch(2, TouchPhase::Moved, Point::new(300.0, 100.0), now);
+        assert!(kinds(&moved).contains(&(GestureKind::Pinch, GesturePhase::Started)));
+        assert!((tracker.scale() - 2.0).abs() < 1e-4);
+
+        let moved = tracker.touch(1, TouchPhase::Moved, Point::new(0.0, 100.0), now);
+        assert!(kinds(&moved).contains(&(GestureKind::Pinch, GesturePhase::Changed)));
+        assert!((tracker.scale() - 3.0).abs() < 1e-4);
+        assert!(tracker.rotation().abs() < 1e-4);
+
+        let ended = tracker.touch(2, TouchPhase::Ended, Point::new(300.0, 100.0), now);
+        assert!(kinds(&ended).contains(&(GestureKind::Pinch, GesturePhase::Ended)));
+        // Lifting one of the fingers doesn't change the scale reached.
+        assert!((tracker.scale() - 3.0).abs() < 1e-4);
+    }
+}
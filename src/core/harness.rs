@@ -0,0 +1,427 @@
+use crate::core::dispatch::{Dispatcher, Platform};
+use crate::core::executor::Executor;
+use crate::core::window::Window;
+use crate::core::*;
+use crate::ui::{Store, UiError, Widget};
+
+use skia_safe::Image;
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+use winit::dpi::{LogicalPosition, PhysicalSize};
+use winit::event::{ElementState, MouseButton, TouchPhase};
+use winit::keyboard::{Key, ModifiersState};
+use winit::window::{WindowAttributes, WindowId};
+
+/// Windows without a display, for testing widget trees with synthetic input.
+///
+/// Inputs are dispatched through the same code as in the [`Application`]'s windows, including
+/// the actions, dialogs and keyboard shortcuts. Windows are painted on CPU raster surfaces whenever
+/// they are [updated](Harness::update), and time only passes when it is [advanced](Harness::advance),
+/// so that [timers](Scheduler) and [tasks](Spawner) run deterministically.
+///
+/// Inputs go to the [selected window](Harness::select_window), which is the first window unless another is selected.
+///
+/// Signals invalidate widgets per thread, so only one harness should be used on a thread at a time.
+pub struct Harness {
+    dispatcher: Dispatcher,
+    platform: HeadlessPlatform,
+    window_id: WindowId,
+    start: Instant,
+    now: Instant,
+    file_drops: Rc<RefCell<Vec<FileDrop>>>,
+    drag_drops: Rc<RefCell<Vec<DragDrop>>>,
+    gestures: Rc<RefCell<Vec<Gesture>>>,
+}
+
+/// Creates headless windows for the [`Dispatcher`] of a [`Harness`].
+struct HeadlessPlatform;
+
+impl Platform for HeadlessPlatform {
+    fn create_window(&mut self, attrs: WindowAttributes) -> Window {
+        Window::headless(&attrs)
+    }
+
+    fn exit(&mut self) {}
+}
+
+impl Harness {
+    /// Creates a harness with a window of the given size, in physical pixels, and a scale factor of 1.
+    pub fn new(size: PhysicalSize<u32>) -> Self {
+        let now = Instant::now();
+        let clipboard = Clipboard::new(MemoryClipboard::new());
+        let mut dispatcher = Dispatcher::new(Executor::new(|| {}), None, clipboard);
+        dispatcher.scheduler.set_virtual_time(now);
+        dispatcher.action_log = Some(Vec::new());
+
+        let file_drops = Rc::new(RefCell::new(Vec::new()));
+        let drag_drops = Rc::new(RefCell::new(Vec::new()));
+        let gestures = Rc::new(RefCell::new(Vec::new()));
+        dispatcher.file_drop_handler = Some(Box::new(collect(&file_drops)));
+        dispatcher.drag_drop_handler = Some(Box::new(collect(&drag_drops)));
+        dispatcher.gesture_handler = Some(Box::new(collect(&gestures)));
+
+        let window = Window::headless(&Window::default_attrs().with_inner_size(size));
+        let window_id = window.id();
+        dispatcher.add_window(window);
+
+        let mut harness = Harness {
+            dispatcher,
+            platform: HeadlessPlatform,
+            window_id,
+            start: now,
+            now,
+            file_drops,
+            drag_drops,
+            gestures,
+        };
+        harness.update();
+        harness
+    }
+
+    /// Returns the store the [loaded](Harness::load_ui) widget trees are bound to.
+    pub fn store(&self) -> &Store {
+        &self.dispatcher.store
+    }
+
+    /// Returns a handle for scheduling timers, which only run when time is [advanced](Harness::advance).
+    pub fn scheduler(&self) -> Scheduler {
+        self.dispatcher.scheduler.clone()
+    }
+
+    /// Returns a handle for spawning futures, which are polled whenever the harness is [updated](Harness::update).
+    pub fn spawner(&self) -> Spawner {
+        self.dispatcher.executor.spawner()
+    }
+
+    /// Returns a handle for an in-memory clipboard, isolated from the system's clipboard and other harnesses.
+    pub fn clipboard(&self) -> Clipboard {
+        self.dispatcher.clipboard.clone()
+    }
+
+    /// Returns the IDs of the open windows, in the order they were opened.
+    pub fn windows(&self) -> Vec<WindowId> {
+        let dispatcher = &self.dispatcher;
+        dispatcher
+            .window_order
+            .iter()
+            .filter(|id| dispatcher.windows.contains_key(id))
+            .copied()
+            .collect()
+    }
+
+    /// Returns the ID of the window inputs are sent to.
+    pub fn window_id(&self) -> WindowId {
+        self.window_id
+    }
+
+    /// Sends the next inputs to the window with the given `id`, e.g. a window or dialog opened by an action.
+    ///
+    /// Returns `false` if there is no such window.
+    pub fn select_window(&mut self, id: WindowId) -> bool {
+        let exists = self.dispatcher.windows.contains_key(&id);
+        if exists {
+            self.window_id = id;
+        }
+        exists
+    }
+
+    /// Returns the window inputs are sent to, or `None` if it has been closed.
+    pub fn window(&self) -> Option<&Window> {
+        self.dispatcher.windows.get(&self.window_id)
+    }
+
+    /// Returns the window inputs are sent to, or `None` if it has been closed.
+    pub fn window_mut(&mut self) -> Option<&mut Window> {
+        self.dispatcher.windows.get_mut(&self.window_id)
+    }
+
+    /// Requests the window to be closed, like its close button, and [updates](Harness::update) the harness.
+    pub fn close_window(&mut self) {
+        self.dispatcher
+            .close_requested(&mut self.platform, self.window_id);
+        self.update();
+    }
+
+    /// Replaces the window's widget tree with the one starting at `root`, and paints it.
+    pub fn set_ui(&mut self, root: Widget) {
+        if let Some(window) = self.window_mut() {
+            window.set_ui(Some(root));
+        }
+        self.update();
+    }
+
+    /// Replaces the window's widget tree with one created from the [`UiDescription`](crate::ui::UiDescription)
+    /// at `path`, binding its texts to the harness' [store](Harness::store), and paints it.
+    pub fn load_ui(&mut self, path: impl Into<PathBuf>) -> Result<(), UiError> {
+        let result = match self.dispatcher.windows.get_mut(&self.window_id) {
+            Some(window) => window.load_ui(path, &self.dispatcher.store),
+            None => Ok(()),
+        };
+        self.update();
+        result
+    }
+
+    /// Returns the root of the window's widget tree, if there is one.
+    pub fn ui(&self) -> Option<&Widget> {
+        self.window()?.ui()
+    }
+
+    /// Finds the widget with the given `id` in the window's widget tree.
+    pub fn find(&self, id: &str) -> Option<&Widget> {
+        self.ui()?.find(id)
+    }
+
+    /// Dispatches an `input` to the window, and [updates](Harness::update) the harness.
+    ///
+    /// Actions triggered by the input are handled like in the application, and can also be retrieved
+    /// with [`take_actions`](Harness::take_actions).
+    pub fn input(&mut self, input: Input) {
+        self.dispatcher
+            .input(&mut self.platform, self.window_id, input, self.now);
+        self.update();
+    }
+
+    /// Moves the cursor to the given position, in logical pixels.
+    pub fn move_cursor(&mut self, x: f32, y: f32) {
+        self.input(Input::CursorMoved(LogicalPosition::new(x, y)));
+    }
+
+    /// Moves the cursor to the given position, in logical pixels, and clicks the left mouse button.
+    pub fn click(&mut self, x: f32, y: f32) {
+        self.move_cursor(x, y);
+        for state in [ElementState::Pressed, ElementState::Released] {
+            self.input(Input::MouseButton {
+                button: MouseButton::Left,
+                state,
+            });
+        }
+    }
+
+    /// Clicks the center of the widget with the given `id`.
+    ///
+    /// Returns `false` if there is no such widget.
+    pub fn click_widget(&mut self, id: &str) -> bool {
+        let bounds = self
+            .ui()
+            .and_then(|root| root.window_bounds(root.find(id)?.key()));
+        let center = match bounds {
+            Some(bounds) => bounds.center(),
+            None => return false,
+        };
+        self.click(center.x, center.y);
+        true
+    }
+
+    /// Presses and releases a `key`. Character keys also produce their text.
+    pub fn press_key(&mut self, key: Key) {
+        let text = match &key {
+            Key::Character(text) => Some(text.to_string()),
+            _ => None,
+        };
+        for state in [ElementState::Pressed, ElementState::Released] {
+            self.input(Input::Key {
+                key: key.clone(),
+                state,
+                repeat: false,
+                text: text.clone().filter(|_| state == ElementState::Pressed),
+            });
+        }
+    }
+
+    /// Types the `text` by pressing the key of each of its characters.
+    pub fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            self.press_key(Key::Character(c.to_string().into()));
+        }
+    }
+
+    /// Changes the modifier keys held down, which apply to the following inputs until changed again.
+    pub fn set_modifiers(&mut self, modifiers: ModifiersState) {
+        self.input(Input::Modifiers(modifiers));
+    }
+
+    /// Scrolls the mouse wheel by the given distance, in logical pixels.
+    pub fn scroll(&mut self, delta_x: f32, delta_y: f32) {
+        self.input(Input::Wheel { delta_x, delta_y });
+    }
+
+    /// Presses the left mouse button at the given position, moves the cursor to the other position
+    /// and releases the button, all in logical pixels, dragging the widget under the cursor if it is draggable.
+    pub fn drag(&mut self, from_x: f32, from_y: f32, to_x: f32, to_y: f32) {
+        self.move_cursor(from_x, from_y);
+        self.input(Input::MouseButton {
+            button: MouseButton::Left,
+            state: ElementState::Pressed,
+        });
+        self.move_cursor(to_x, to_y);
+        self.input(Input::MouseButton {
+            button: MouseButton::Left,
+            state: ElementState::Released,
+        });
+    }
+
+    /// Touches, moves or lifts the finger with the given `id` at the given position, in logical pixels.
+    pub fn touch(&mut self, id: u64, phase: TouchPhase, x: f32, y: f32) {
+        self.input(Input::Touch {
+            id,
+            phase,
+            position: LogicalPosition::new(x, y),
+        });
+    }
+
+    /// Taps the given position, in logical pixels, with a finger.
+    pub fn tap(&mut self, x: f32, y: f32) {
+        for phase in [TouchPhase::Started, TouchPhase::Ended] {
+            self.touch(0, phase, x, y);
+        }
+    }
+
+    /// Returns the fingers touching the window.
+    pub fn touches(&self) -> Vec<TouchPoint> {
+        self.window().map(Window::touches).unwrap_or_default()
+    }
+
+    /// Sets whether the mouse emulates touches, like [`Window::set_touch_emulation`].
+    pub fn set_touch_emulation(&mut self, emulation: bool) {
+        if let Some(window) = self.window_mut() {
+            window.set_touch_emulation(emulation);
+        }
+    }
+
+    /// Drags files from another application to the given position, in logical pixels, and drops them.
+    pub fn drop_files<P: Into<PathBuf>>(
+        &mut self,
+        x: f32,
+        y: f32,
+        paths: impl IntoIterator<Item = P>,
+    ) {
+        let paths: Vec<PathBuf> = paths.into_iter().map(Into::into).collect();
+        for path in &paths {
+            self.input(Input::FileHovered(path.clone()));
+        }
+        self.move_cursor(x, y);
+        for path in paths {
+            self.input(Input::FileDropped(path));
+        }
+    }
+
+    /// Resizes the window to the given size, in physical pixels.
+    pub fn resize(&mut self, size: PhysicalSize<u32>) {
+        self.input(Input::Resized(size));
+    }
+
+    /// Changes the window's scale factor.
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.input(Input::ScaleFactorChanged(scale_factor));
+    }
+
+    /// Advances the time by `duration`, running the timers that are due, and [updates](Harness::update) the harness.
+    pub fn advance(&mut self, duration: Duration) {
+        self.now += duration;
+        let dispatcher = &mut self.dispatcher;
+        dispatcher.scheduler.set_virtual_time(self.now);
+        dispatcher.scheduler.run_due(self.now);
+        dispatcher.recognize_long_presses(self.now);
+        dispatcher.auto_scroll(self.now);
+        self.update();
+    }
+
+    /// Replays the inputs of a [`Recording`] to the windows opened in the same order as when it was recorded,
+    /// [advancing](Harness::advance) the time since the harness was created to when each input was received.
+    pub fn replay(&mut self, recording: &Recording) {
+        for event in recording.events() {
+            let elapsed = self.now - self.start;
+            if let Some(delay) = event.time.checked_sub(elapsed) {
+                self.advance(delay);
+            }
+            if let Some(&window_id) = self.dispatcher.window_order.get(event.window) {
+                self.dispatcher
+                    .input(&mut self.platform, window_id, event.input.clone(), self.now);
+                self.update();
+            }
+        }
+    }
+
+    /// Polls the spawned tasks that are ready, marks the widgets depending on changed
+    /// [`Signal`](crate::ui::Signal)s as dirty, resizes the windows that requested it, and repaints
+    /// what has changed, like the application does between events.
+    pub fn update(&mut self) {
+        let dispatcher = &mut self.dispatcher;
+        dispatcher.executor.run_ready();
+        dispatcher.notify_window_changes();
+        dispatcher.open_requested_dialogs(&mut self.platform);
+        dispatcher.redraw_invalidated_windows();
+
+        let window_ids: Vec<_> = dispatcher.windows.keys().copied().collect();
+        for window_id in &window_ids {
+            let size = dispatcher
+                .windows
+                .get(window_id)
+                .and_then(Window::take_requested_size);
+            if let Some(size) = size {
+                dispatcher.input(
+                    &mut self.platform,
+                    *window_id,
+                    Input::Resized(size),
+                    self.now,
+                );
+            }
+        }
+        for window_id in window_ids {
+            let redraw = dispatcher
+                .windows
+                .get(&window_id)
+                .is_some_and(Window::take_redraw_request);
+            if redraw {
+                dispatcher.redraw(window_id);
+            }
+        }
+    }
+
+    /// Returns the actions triggered since the last call, from the oldest to the newest.
+    pub fn take_actions(&mut self) -> Vec<String> {
+        self.dispatcher
+            .action_log
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// Returns the files dropped on drop targets since the last call, from the oldest to the newest.
+    pub fn take_file_drops(&mut self) -> Vec<FileDrop> {
+        self.file_drops.take()
+    }
+
+    /// Returns the payloads dragged and dropped on drop targets since the last call, from the oldest to the newest.
+    pub fn take_drag_drops(&mut self) -> Vec<DragDrop> {
+        self.drag_drops.take()
+    }
+
+    /// Returns the gestures recognized by the widgets since the last call, from the oldest to the newest.
+    ///
+    /// Long presses are recognized when time is [advanced](Harness::advance) while a finger is held.
+    pub fn take_gestures(&mut self) -> Vec<Gesture> {
+        self.gestures.take()
+    }
+
+    /// Returns an image of the window's current content.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the window has been closed.
+    pub fn snapshot(&mut self) -> Image {
+        self.window_mut()
+            .expect("The harness' window has been closed")
+            .snapshot()
+    }
+}
+
+/// Returns a handler pushing the events it receives to `events`.
+fn collect<E: Clone + 'static>(
+    events: &Rc<RefCell<Vec<E>>>,
+) -> impl FnMut(&mut Window, &E, &Store) + 'static {
+    let events = events.clone();
+    move |_, event, _| events.borrow_mut().push(event.clone())
+}
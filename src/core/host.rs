@@ -0,0 +1,365 @@
+use crate::core::window::RawWindow;
+
+use std::cell::{Cell, RefCell};
+use std::sync::atomic::{AtomicU64, Ordering};
+use winit::dpi::{PhysicalPosition, PhysicalSize, Position, Size};
+use winit::error::ExternalError;
+use winit::monitor::MonitorHandle;
+use winit::window::{
+    CursorGrabMode, Fullscreen, ResizeDirection, WindowAttributes, WindowId, WindowLevel,
+};
+
+/// Size of headless windows created without an inner size, in physical pixels.
+const DEFAULT_HEADLESS_SIZE: PhysicalSize<u32> = PhysicalSize::new(800, 600);
+
+/// The OS window a [`Window`](crate::core::Window) is shown in, or a headless stand-in for it.
+///
+/// The methods mirror the ones of `winit`'s windows that are used by [`Window`](crate::core::Window),
+/// so that it works the same way with and without a display.
+pub(super) enum Host {
+    /// A window on the display.
+    Raw(RawWindow),
+    /// A window without a display, used by the [`Harness`](crate::core::Harness).
+    Headless(HeadlessWindow),
+}
+
+/// A window without a display, which keeps the state the OS would keep for a real window.
+///
+/// Its size and scale factor only change with the [`Input`](crate::core::Input)s the OS would send,
+/// and requests to resize it are applied once they are [taken](HeadlessWindow::take_requested_size).
+pub(super) struct HeadlessWindow {
+    id: WindowId,
+    title: RefCell<String>,
+    size: Cell<PhysicalSize<u32>>,
+    requested_size: Cell<Option<PhysicalSize<u32>>>,
+    scale_factor: Cell<f64>,
+    fullscreen: RefCell<Option<Fullscreen>>,
+    maximized: Cell<bool>,
+    minimized: Cell<bool>,
+    decorated: Cell<bool>,
+    resizable: Cell<bool>,
+    redraw_requested: Cell<bool>,
+}
+
+impl HeadlessWindow {
+    /// Creates a headless window with the given `attrs`, and a scale factor of 1.
+    pub(super) fn new(attrs: &WindowAttributes) -> Self {
+        // Starting from the largest IDs makes it unlikely to collide with IDs of real windows.
+        static NEXT_ID: AtomicU64 = AtomicU64::new(u64::MAX);
+
+        let size = attrs
+            .inner_size
+            .map_or(DEFAULT_HEADLESS_SIZE, |size| size.to_physical(1.0));
+        HeadlessWindow {
+            id: WindowId::from(NEXT_ID.fetch_sub(1, Ordering::Relaxed)),
+            title: RefCell::new(attrs.title.clone()),
+            size: Cell::new(size),
+            requested_size: Cell::new(None),
+            scale_factor: Cell::new(1.0),
+            fullscreen: RefCell::new(attrs.fullscreen.clone()),
+            maximized: Cell::new(attrs.maximized),
+            minimized: Cell::new(false),
+            decorated: Cell::new(attrs.decorations),
+            resizable: Cell::new(attrs.resizable),
+            redraw_requested: Cell::new(false),
+        }
+    }
+
+    /// Returns the size requested since the last call, in physical pixels, if any.
+    pub(super) fn take_requested_size(&self) -> Option<PhysicalSize<u32>> {
+        self.requested_size.take()
+    }
+
+    /// Returns `true` if a redraw has been requested since the last call.
+    pub(super) fn take_redraw_request(&self) -> bool {
+        self.redraw_requested.take()
+    }
+}
+
+impl Host {
+    /// Returns the headless window, if the window has no display.
+    pub(super) fn headless(&self) -> Option<&HeadlessWindow> {
+        match self {
+            Host::Raw(_) => None,
+            Host::Headless(headless) => Some(headless),
+        }
+    }
+
+    /// Updates the size of a headless window once it has been resized, like the OS does for real windows.
+    pub(super) fn resized(&self, size: PhysicalSize<u32>) {
+        if let Host::Headless(headless) = self {
+            headless.size.set(size);
+        }
+    }
+
+    /// Updates the scale factor of a headless window once it has changed, like the OS does for real windows.
+    pub(super) fn scale_factor_changed(&self, scale_factor: f64) {
+        if let Host::Headless(headless) = self {
+            headless.scale_factor.set(scale_factor);
+        }
+    }
+
+    pub(super) fn id(&self) -> WindowId {
+        match self {
+            Host::Raw(raw) => raw.id(),
+            Host::Headless(headless) => headless.id,
+        }
+    }
+
+    pub(super) fn title(&self) -> String {
+        match self {
+            Host::Raw(raw) => raw.title(),
+            Host::Headless(headless) => headless.title.borrow().clone(),
+        }
+    }
+
+    pub(super) fn set_title(&self, title: &str) {
+        match self {
+            Host::Raw(raw) => raw.set_title(title),
+            Host::Headless(headless) => *headless.title.borrow_mut() = title.to_string(),
+        }
+    }
+
+    pub(super) fn scale_factor(&self) -> f64 {
+        match self {
+            Host::Raw(raw) => raw.scale_factor(),
+            Host::Headless(headless) => headless.scale_factor.get(),
+        }
+    }
+
+    pub(super) fn inner_size(&self) -> PhysicalSize<u32> {
+        match self {
+            Host::Raw(raw) => raw.inner_size(),
+            Host::Headless(headless) => headless.size.get(),
+        }
+    }
+
+    /// Headless windows have no decorations around their content.
+    pub(super) fn outer_size(&self) -> PhysicalSize<u32> {
+        match self {
+            Host::Raw(raw) => raw.outer_size(),
+            Host::Headless(headless) => headless.size.get(),
+        }
+    }
+
+    pub(super) fn request_inner_size(&self, size: impl Into<Size>) {
+        match self {
+            Host::Raw(raw) => {
+                let _ = raw.request_inner_size(size);
+            }
+            Host::Headless(headless) => {
+                let size = size.into().to_physical(headless.scale_factor.get());
+                headless.requested_size.set(Some(size));
+            }
+        }
+    }
+
+    /// Returns `None` if the platform doesn't report where windows are, which headless windows don't.
+    pub(super) fn inner_position(&self) -> Option<PhysicalPosition<i32>> {
+        match self {
+            Host::Raw(raw) => raw.inner_position().ok(),
+            Host::Headless(_) => None,
+        }
+    }
+
+    /// Returns `None` if the platform doesn't report where windows are, which headless windows don't.
+    pub(super) fn outer_position(&self) -> Option<PhysicalPosition<i32>> {
+        match self {
+            Host::Raw(raw) => raw.outer_position().ok(),
+            Host::Headless(_) => None,
+        }
+    }
+
+    pub(super) fn set_outer_position(&self, position: impl Into<Position>) {
+        if let Host::Raw(raw) = self {
+            raw.set_outer_position(position);
+        }
+    }
+
+    pub(super) fn request_redraw(&self) {
+        match self {
+            Host::Raw(raw) => raw.request_redraw(),
+            Host::Headless(headless) => headless.redraw_requested.set(true),
+        }
+    }
+
+    pub(super) fn pre_present_notify(&self) {
+        if let Host::Raw(raw) = self {
+            raw.pre_present_notify();
+        }
+    }
+
+    pub(super) fn focus_window(&self) {
+        if let Host::Raw(raw) = self {
+            raw.focus_window();
+        }
+    }
+
+    /// Headless windows are always focused, since they don't compete for the keyboard.
+    pub(super) fn has_focus(&self) -> bool {
+        match self {
+            Host::Raw(raw) => raw.has_focus(),
+            Host::Headless(_) => true,
+        }
+    }
+
+    pub(super) fn fullscreen(&self) -> Option<Fullscreen> {
+        match self {
+            Host::Raw(raw) => raw.fullscreen(),
+            Host::Headless(headless) => headless.fullscreen.borrow().clone(),
+        }
+    }
+
+    pub(super) fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
+        match self {
+            Host::Raw(raw) => raw.set_fullscreen(fullscreen),
+            Host::Headless(headless) => *headless.fullscreen.borrow_mut() = fullscreen,
+        }
+    }
+
+    pub(super) fn available_monitors(&self) -> Vec<MonitorHandle> {
+        match self {
+            Host::Raw(raw) => raw.available_monitors().collect(),
+            Host::Headless(_) => Vec::new(),
+        }
+    }
+
+    pub(super) fn primary_monitor(&self) -> Option<MonitorHandle> {
+        match self {
+            Host::Raw(raw) => raw.primary_monitor(),
+            Host::Headless(_) => None,
+        }
+    }
+
+    pub(super) fn current_monitor(&self) -> Option<MonitorHandle> {
+        match self {
+            Host::Raw(raw) => raw.current_monitor(),
+            Host::Headless(_) => None,
+        }
+    }
+
+    pub(super) fn is_maximized(&self) -> bool {
+        match self {
+            Host::Raw(raw) => raw.is_maximized(),
+            Host::Headless(headless) => headless.maximized.get(),
+        }
+    }
+
+    pub(super) fn set_maximized(&self, maximized: bool) {
+        match self {
+            Host::Raw(raw) => raw.set_maximized(maximized),
+            Host::Headless(headless) => headless.maximized.set(maximized),
+        }
+    }
+
+    pub(super) fn is_minimized(&self) -> Option<bool> {
+        match self {
+            Host::Raw(raw) => raw.is_minimized(),
+            Host::Headless(headless) => Some(headless.minimized.get()),
+        }
+    }
+
+    pub(super) fn set_minimized(&self, minimized: bool) {
+        match self {
+            Host::Raw(raw) => raw.set_minimized(minimized),
+            Host::Headless(headless) => headless.minimized.set(minimized),
+        }
+    }
+
+    pub(super) fn set_window_level(&self, level: WindowLevel) {
+        if let Host::Raw(raw) = self {
+            raw.set_window_level(level);
+        }
+    }
+
+    pub(super) fn is_decorated(&self) -> bool {
+        match self {
+            Host::Raw(raw) => raw.is_decorated(),
+            Host::Headless(headless) => headless.decorated.get(),
+        }
+    }
+
+    pub(super) fn set_decorations(&self, decorations: bool) {
+        match self {
+            Host::Raw(raw) => raw.set_decorations(decorations),
+            Host::Headless(headless) => headless.decorated.set(decorations),
+        }
+    }
+
+    pub(super) fn is_resizable(&self) -> bool {
+        match self {
+            Host::Raw(raw) => raw.is_resizable(),
+            Host::Headless(headless) => headless.resizable.get(),
+        }
+    }
+
+    pub(super) fn set_resizable(&self, resizable: bool) {
+        match self {
+            Host::Raw(raw) => raw.set_resizable(resizable),
+            Host::Headless(headless) => headless.resizable.set(resizable),
+        }
+    }
+
+    pub(super) fn set_min_inner_size(&self, size: Option<impl Into<Size>>) {
+        if let Host::Raw(raw) = self {
+            raw.set_min_inner_size(size);
+        }
+    }
+
+    pub(super) fn set_max_inner_size(&self, size: Option<impl Into<Size>>) {
+        if let Host::Raw(raw) = self {
+            raw.set_max_inner_size(size);
+        }
+    }
+
+    pub(super) fn set_blur(&self, blur: bool) {
+        if let Host::Raw(raw) = self {
+            raw.set_blur(blur);
+        }
+    }
+
+    pub(super) fn set_cursor_hittest(&self, hittest: bool) -> Result<(), ExternalError> {
+        match self {
+            Host::Raw(raw) => raw.set_cursor_hittest(hittest),
+            Host::Headless(_) => Ok(()),
+        }
+    }
+
+    pub(super) fn set_cursor(&self, cursor: impl Into<winit::window::Cursor>) {
+        if let Host::Raw(raw) = self {
+            raw.set_cursor(cursor);
+        }
+    }
+
+    pub(super) fn set_cursor_visible(&self, visible: bool) {
+        if let Host::Raw(raw) = self {
+            raw.set_cursor_visible(visible);
+        }
+    }
+
+    pub(super) fn set_cursor_grab(&self, mode: CursorGrabMode) -> Result<(), ExternalError> {
+        match self {
+            Host::Raw(raw) => raw.set_cursor_grab(mode),
+            Host::Headless(_) => Ok(()),
+        }
+    }
+
+    /// Headless windows can't be moved by the user, so this does nothing for them.
+    pub(super) fn drag_window(&self) -> Result<(), ExternalError> {
+        match self {
+            Host::Raw(raw) => raw.drag_window(),
+            Host::Headless(_) => Ok(()),
+        }
+    }
+
+    /// Headless windows can't be resized by the user, so this does nothing for them.
+    pub(super) fn drag_resize_window(
+        &self,
+        direction: ResizeDirection,
+    ) -> Result<(), ExternalError> {
+        match self {
+            Host::Raw(raw) => raw.drag_resize_window(direction),
+            Host::Headless(_) => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+use winit::window::WindowId;
+
+/// Watches the files that the contents of windows are created from
+/// and reports which windows have to be rebuilt when they change.
+///
+/// Parent directories are watched instead of the files themselves,
+/// so that files replaced by editors on save are still tracked.
+pub(super) struct HotReloader {
+    watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<Event>>,
+    dependents: HashMap<PathBuf, HashSet<WindowId>>,
+    watched_dirs: HashSet<PathBuf>,
+}
+
+impl HotReloader {
+    /// Creates a new reloader, without any watched files.
+    ///
+    /// `wake` is called from the watcher's thread whenever a file event is received,
+    /// so that the event loop can pick it up with [`changed_windows`](Self::changed_windows).
+    pub(super) fn new(wake: impl Fn() + Send + 'static) -> notify::Result<Self> {
+        let (sender, events) = channel();
+        let watcher = notify::recommended_watcher(move |event| {
+            if sender.send(event).is_ok() {
+                wake();
+            }
+        })?;
+        Ok(HotReloader {
+            watcher,
+            events,
+            dependents: HashMap::new(),
+            watched_dirs: HashSet::new(),
+        })
+    }
+
+    /// Replaces the files the window with the given `window_id` depends on.
+    pub(super) fn watch(&mut self, window_id: WindowId, paths: Vec<PathBuf>) {
+        self.remove_window(window_id);
+        for path in paths {
+            let path = path.canonicalize().unwrap_or(path);
+            self.dependents.entry(path).or_default().insert(window_id);
+        }
+        self.sync_watched_dirs();
+    }
+
+    /// Stops watching the files the window with the given `window_id` depends on.
+    pub(super) fn unwatch(&mut self, window_id: WindowId) {
+        self.remove_window(window_id);
+        self.sync_watched_dirs();
+    }
+
+    /// Returns the IDs of the windows whose files have changed since the last call.
+    pub(super) fn changed_windows(&mut self) -> HashSet<WindowId> {
+        let mut changed = HashSet::new();
+        for event in self.events.try_iter() {
+            let event = match event {
+                Ok(event) => event,
+                Err(e) => {
+                    eprintln!("File watcher error: {e}");
+                    continue;
+                }
+            };
+            if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                continue;
+            }
+            for path in &event.paths {
+                if let Some(windows) = self.dependents.get(path) {
+                    changed.extend(windows);
+                }
+            }
+        }
+        changed
+    }
+
+    fn remove_window(&mut self, window_id: WindowId) {
+        self.dependents.retain(|_, windows| {
+            windows.remove(&window_id);
+            !windows.is_empty()
+        });
+    }
+
+    /// Starts watching the directories of newly added files and stops watching
+    /// the directories without any files left.
+    fn sync_watched_dirs(&mut self) {
+        let dirs: HashSet<PathBuf> = self
+            .dependents
+            .keys()
+            .filter_map(|path| path.parent().map(Path::to_path_buf))
+            .collect();
+        for dir in self.watched_dirs.difference(&dirs) {
+            let _ = self.watcher.unwatch(dir);
+        }
+        for dir in dirs.difference(&self.watched_dirs) {
+            if let Err(e) = self.watcher.watch(dir, RecursiveMode::NonRecursive) {
+                eprintln!("Could not watch {}: {e}", dir.display());
+            }
+        }
+        self.watched_dirs = dirs;
+    }
+}
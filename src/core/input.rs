@@ -0,0 +1,203 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use winit::dpi::{LogicalPosition, PhysicalSize};
+use winit::event::{
+    ElementState, Ime, KeyEvent, MouseButton, MouseScrollDelta, Touch, TouchPhase, WindowEvent,
+};
+use winit::keyboard::{Key, ModifiersState};
+
+/// Logical pixels scrolled per line of a [`MouseScrollDelta::LineDelta`].
+const PIXELS_PER_LINE: f32 = 20.0;
+
+/// An input event delivered to a window.
+///
+/// Window events from the OS are converted to inputs with [`Input::from_window_event`],
+/// and inputs can also be created directly to simulate the user, e.g. in a [`Harness`](crate::core::Harness).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Input {
+    /// The cursor has moved to the given position, in logical pixels.
+    CursorMoved(LogicalPosition<f32>),
+    /// The cursor has left the window.
+    CursorLeft,
+    /// A mouse button has been pressed or released.
+    MouseButton {
+        /// The button.
+        button: MouseButton,
+        /// Whether the button has been pressed or released.
+        state: ElementState,
+    },
+    /// A key has been pressed or released.
+    Key {
+        /// The key, taking the keyboard layout and modifiers into account.
+        key: Key,
+        /// Whether the key has been pressed or released.
+        state: ElementState,
+        /// `true` if the key is being held down and the press is repeated.
+        repeat: bool,
+        /// The text produced by pressing the key, if any.
+        text: Option<String>,
+    },
+    /// Text has been committed by an input method.
+    Text(String),
+    /// The modifier keys held down have changed.
+    Modifiers(ModifiersState),
+    /// The mouse wheel or touchpad has been scrolled by the given amount, in logical pixels.
+    Wheel {
+        /// Horizontal distance, positive to the right.
+        delta_x: f32,
+        /// Vertical distance, positive downwards.
+        delta_y: f32,
+    },
+    /// The mouse has moved by the given amount while the cursor is [locked](crate::core::Window::set_cursor_grab),
+    /// in device units that are not accelerated or clamped to the screen.
+    MouseMotion {
+        /// Horizontal distance, positive to the right.
+        delta_x: f32,
+        /// Vertical distance, positive downwards.
+        delta_y: f32,
+    },
+    /// A finger has touched, moved on or left the window's touch screen.
+    Touch {
+        /// The ID of the finger, unique while it touches the screen.
+        id: u64,
+        /// Whether the finger has touched, moved on or left the screen.
+        phase: TouchPhase,
+        /// The position of the finger, in logical pixels.
+        position: LogicalPosition<f32>,
+    },
+    /// Two fingers have moved apart or together on a touchpad.
+    PinchGesture {
+        /// The change of the distance between the fingers relative to it, positive when they move apart.
+        delta: f32,
+        /// The progress of the gesture.
+        phase: TouchPhase,
+    },
+    /// Fingers have moved together on a touchpad, by the given amount in logical pixels.
+    PanGesture {
+        /// Horizontal distance, positive to the right.
+        delta_x: f32,
+        /// Vertical distance, positive downwards.
+        delta_y: f32,
+        /// The progress of the gesture.
+        phase: TouchPhase,
+    },
+    /// Two fingers have turned on a touchpad.
+    RotationGesture {
+        /// The angle, in degrees, positive counterclockwise.
+        delta: f32,
+        /// The progress of the gesture.
+        phase: TouchPhase,
+    },
+    /// A touchpad has been double tapped, e.g. for smart magnification.
+    DoubleTapGesture,
+    /// A file dragged from another application has entered the window.
+    /// Each file of a drag is reported separately.
+    FileHovered(PathBuf),
+    /// The files dragged over the window have left it without being dropped.
+    FileHoverCancelled,
+    /// A file dragged from another application has been dropped on the window.
+    /// Each file of a drop is reported separately.
+    FileDropped(PathBuf),
+    /// The window's content has been resized.
+    Resized(PhysicalSize<u32>),
+    /// The window has been moved to a display with a different scale factor.
+    ScaleFactorChanged(f64),
+}
+
+impl Input {
+    /// Converts a window `event` to an input, if it is one.
+    ///
+    /// `scale_factor` is the window's scale factor, used to convert physical positions to logical ones.
+    pub fn from_window_event(event: &WindowEvent, scale_factor: f64) -> Option<Input> {
+        let input = match event {
+            WindowEvent::CursorMoved { position, .. } => {
+                Input::CursorMoved(position.to_logical(scale_factor))
+            }
+            WindowEvent::CursorLeft { .. } => Input::CursorLeft,
+            WindowEvent::MouseInput { button, state, .. } => Input::MouseButton {
+                button: *button,
+                state: *state,
+            },
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        logical_key,
+                        state,
+                        repeat,
+                        text,
+                        ..
+                    },
+                ..
+            } => Input::Key {
+                key: logical_key.clone(),
+                state: *state,
+                repeat: *repeat,
+                text: text.as_ref().map(ToString::to_string),
+            },
+            WindowEvent::Ime(Ime::Commit(text)) => Input::Text(text.clone()),
+            WindowEvent::ModifiersChanged(modifiers) => Input::Modifiers(modifiers.state()),
+            WindowEvent::MouseWheel { delta, .. } => {
+                let (delta_x, delta_y) = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => (x * PIXELS_PER_LINE, y * PIXELS_PER_LINE),
+                    MouseScrollDelta::PixelDelta(position) => {
+                        let position: LogicalPosition<f32> = position.to_logical(scale_factor);
+                        (position.x, position.y)
+                    }
+                };
+                // Winit reports positive deltas when scrolling up or left.
+                Input::Wheel {
+                    delta_x: -delta_x,
+                    delta_y: -delta_y,
+                }
+            }
+            WindowEvent::Touch(Touch {
+                id,
+                phase,
+                location,
+                ..
+            }) => Input::Touch {
+                id: *id,
+                phase: *phase,
+                position: location.to_logical(scale_factor),
+            },
+            WindowEvent::PinchGesture { delta, phase, .. } => Input::PinchGesture {
+                delta: *delta as f32,
+                phase: *phase,
+            },
+            WindowEvent::PanGesture { delta, phase, .. } => {
+                let delta: LogicalPosition<f32> = delta.to_logical(scale_factor);
+                Input::PanGesture {
+                    delta_x: delta.x,
+                    delta_y: delta.y,
+                    phase: *phase,
+                }
+            }
+            WindowEvent::RotationGesture { delta, phase, .. } => Input::RotationGesture {
+                delta: *delta,
+                phase: *phase,
+            },
+            WindowEvent::DoubleTapGesture { .. } => Input::DoubleTapGesture,
+            WindowEvent::HoveredFile(path) => Input::FileHovered(path.clone()),
+            WindowEvent::HoveredFileCancelled => Input::FileHoverCancelled,
+            WindowEvent::DroppedFile(path) => Input::FileDropped(path.clone()),
+            WindowEvent::Resized(size) => Input::Resized(*size),
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                Input::ScaleFactorChanged(*scale_factor)
+            }
+            _ => return None,
+        };
+        Some(input)
+    }
+
+    /// Returns `true` if the input comes from the user interacting with the window's content,
+    /// rather than from the OS changing the window or the cursor leaving it.
+    pub(super) fn is_user_input(&self) -> bool {
+        !matches!(
+            self,
+            Input::CursorLeft
+                | Input::FileHoverCancelled
+                | Input::Resized(_)
+                | Input::ScaleFactorChanged(_)
+        )
+    }
+}
@@ -0,0 +1,66 @@
+use winit::dpi::{LogicalSize, PhysicalPosition, PhysicalSize};
+use winit::monitor::{MonitorHandle, VideoModeHandle};
+
+/// A display connected to the computer, as returned by [`Window::monitors`](crate::core::Window::monitors).
+///
+/// Positions and sizes are in physical pixels, in the desktop's coordinate space shared by all monitors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Monitor {
+    handle: MonitorHandle,
+}
+
+impl Monitor {
+    pub(super) fn new(handle: MonitorHandle) -> Self {
+        Monitor { handle }
+    }
+
+    /// Returns the monitor's name, if the platform reports it.
+    pub fn name(&self) -> Option<String> {
+        self.handle.name()
+    }
+
+    /// Returns the position of the monitor's top-left corner on the desktop.
+    pub fn position(&self) -> PhysicalPosition<i32> {
+        self.handle.position()
+    }
+
+    /// Returns the monitor's resolution.
+    pub fn size(&self) -> PhysicalSize<u32> {
+        self.handle.size()
+    }
+
+    /// Returns the monitor's size in logical pixels, the unit widgets and drawings use.
+    pub fn logical_size(&self) -> LogicalSize<f64> {
+        self.size().to_logical(self.scale_factor())
+    }
+
+    /// Returns the scale factor used to map logical pixels to the monitor's physical pixels.
+    pub fn scale_factor(&self) -> f64 {
+        self.handle.scale_factor()
+    }
+
+    /// Returns the monitor's refresh rate in hertz, if the platform reports it.
+    pub fn refresh_rate(&self) -> Option<f64> {
+        self.handle
+            .refresh_rate_millihertz()
+            .map(|millihertz| millihertz as f64 / 1000.0)
+    }
+
+    /// Returns the video modes the monitor supports, for exclusive fullscreen.
+    pub fn video_modes(&self) -> Vec<VideoModeHandle> {
+        self.handle.video_modes().collect()
+    }
+
+    /// Returns the position that centers a window with the given outer `size` on the monitor.
+    pub(super) fn center(&self, size: PhysicalSize<u32>) -> PhysicalPosition<i32> {
+        let (position, extent) = (self.position(), self.size());
+        PhysicalPosition::new(
+            position.x + (extent.width as i32 - size.width as i32) / 2,
+            position.y + (extent.height as i32 - size.height as i32) / 2,
+        )
+    }
+
+    pub(super) fn handle(&self) -> &MonitorHandle {
+        &self.handle
+    }
+}
@@ -0,0 +1,23 @@
+use skia_safe::image::CachingHint;
+use skia_safe::{AlphaType, ColorType, ISize, Image, ImageInfo};
+
+/// Returns the image info of unpremultiplied RGBA pixels of the given size,
+/// the format of PNG files and of the images on the clipboard.
+pub(super) fn rgba_info(size: ISize) -> ImageInfo {
+    ImageInfo::new(size, ColorType::RGBA8888, AlphaType::Unpremul, None)
+}
+
+/// Returns the unpremultiplied RGBA pixels of `image`, row by row,
+/// or `None` if they can't be read.
+pub(super) fn read_rgba(image: &Image) -> Option<Vec<u8>> {
+    let info = rgba_info(image.dimensions());
+    let mut pixels = vec![0; info.compute_min_byte_size()];
+    let read = image.read_pixels(
+        &info,
+        &mut pixels,
+        info.min_row_bytes(),
+        (0, 0),
+        CachingHint::Allow,
+    );
+    read.then_some(pixels)
+}
@@ -0,0 +1,232 @@
+use crate::core::*;
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt::{self, Display, Formatter};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// An input received by one of the application's windows during a recorded session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    /// The time since the start of the session.
+    pub time: Duration,
+    /// The index of the window, in the order the windows were opened, starting with 0 for the first window.
+    pub window: usize,
+    /// The input.
+    pub input: Input,
+}
+
+/// The inputs received by an application's windows during a session, in the order they were received.
+///
+/// Sessions are recorded with [`Application::with_recording`] to a file with one RON-encoded
+/// [`RecordedEvent`] per line, and can be replayed with [`Application::with_replay`] or [`Harness::replay`].
+#[derive(Debug, Clone, Default)]
+pub struct Recording {
+    events: Vec<RecordedEvent>,
+}
+
+/// An error that occurred while loading a [`Recording`].
+#[derive(Debug)]
+pub enum RecordingError {
+    /// The file could not be read.
+    Io(PathBuf, io::Error),
+    /// The given line is not a valid [`RecordedEvent`].
+    Parse(usize, ron::error::SpannedError),
+}
+
+impl Recording {
+    /// Loads a recording from the file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RecordingError> {
+        let path = path.as_ref();
+        let source =
+            fs::read_to_string(path).map_err(|e| RecordingError::Io(path.to_path_buf(), e))?;
+        Recording::parse(&source)
+    }
+
+    /// Parses a recording with one RON-encoded [`RecordedEvent`] per line. Empty lines are skipped.
+    pub fn parse(source: &str) -> Result<Self, RecordingError> {
+        let events = source
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| ron::from_str(line).map_err(|e| RecordingError::Parse(i + 1, e)))
+            .collect::<Result<_, _>>()?;
+        Ok(Recording { events })
+    }
+
+    /// Returns the recorded events, from the oldest to the newest.
+    pub fn events(&self) -> &[RecordedEvent] {
+        &self.events
+    }
+}
+
+/// Writes the inputs received by the application's windows to a file as they arrive.
+pub(super) struct Recorder {
+    writer: BufWriter<File>,
+    start: Instant,
+}
+
+impl Recorder {
+    /// Starts recording a session to the file at `path`, replacing it if it exists.
+    pub(super) fn new(path: &Path) -> io::Result<Self> {
+        Ok(Recorder {
+            writer: BufWriter::new(File::create(path)?),
+            start: Instant::now(),
+        })
+    }
+
+    /// Records an `input` received by the window with the given index.
+    ///
+    /// Every event is flushed right away, so that the recording survives a crash.
+    pub(super) fn record(&mut self, window: usize, input: &Input) -> io::Result<()> {
+        let event = RecordedEvent {
+            time: self.start.elapsed(),
+            window,
+            input: input.clone(),
+        };
+        let line = ron::to_string(&event).map_err(io::Error::other)?;
+        writeln!(self.writer, "{line}")?;
+        self.writer.flush()
+    }
+}
+
+/// Delivers the events of a [`Recording`] at the same times, relative to its start, as they were recorded.
+pub(super) struct Replay {
+    events: VecDeque<RecordedEvent>,
+    start: Instant,
+}
+
+impl Replay {
+    /// Starts replaying a `recording` from now.
+    pub(super) fn new(recording: Recording) -> Self {
+        Replay {
+            events: recording.events.into(),
+            start: Instant::now(),
+        }
+    }
+
+    /// Removes and returns the events that are due at `now`, from the oldest to the newest.
+    pub(super) fn take_due(&mut self, now: Instant) -> Vec<RecordedEvent> {
+        let elapsed = now.saturating_duration_since(self.start);
+        let due = self.events.iter().take_while(|e| e.time <= elapsed).count();
+        self.events.drain(..due).collect()
+    }
+
+    /// Returns the time the next event is due, if there are events left.
+    pub(super) fn next_deadline(&self) -> Option<Instant> {
+        self.events.front().map(|event| self.start + event.time)
+    }
+}
+
+impl Display for RecordingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordingError::Io(path, e) => write!(f, "Could not read {}: {e}", path.display()),
+            RecordingError::Parse(line, e) => write!(f, "Invalid event on line {line}: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for RecordingError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RecordingError::Io(_, e) => Some(e),
+            RecordingError::Parse(_, e) => Some(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use winit::dpi::{LogicalPosition, PhysicalSize};
+    use winit::event::{ElementState, MouseButton};
+    use winit::keyboard::{Key, NamedKey};
+
+    fn events() -> Vec<RecordedEvent> {
+        let inputs = [
+            Input::CursorMoved(LogicalPosition::new(12.5, 40.0)),
+            Input::MouseButton {
+                button: MouseButton::Left,
+                state: ElementState::Pressed,
+            },
+            Input::Key {
+                key: Key::Named(NamedKey::Enter),
+                state: ElementState::Released,
+                repeat: false,
+                text: Some("\n".into()),
+            },
+            Input::FileDropped("/tmp/a file.png".into()),
+            Input::Resized(PhysicalSize::new(1024, 768)),
+        ];
+        inputs
+            .into_iter()
+            .enumerate()
+            .map(|(i, input)| RecordedEvent {
+                time: Duration::from_millis(i as u64 * 250),
+                window: i % 2,
+                input,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn recorded_events_are_parsed_back() {
+        let path = env::temp_dir().join(format!("recording-{}.ron", std::process::id()));
+        let mut recorder = Recorder::new(&path).unwrap();
+        for event in events() {
+            recorder.record(event.window, &event.input).unwrap();
+        }
+        let recording = Recording::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let received = |events: &[RecordedEvent]| {
+            let received = events.iter().map(|e| (e.window, e.input.clone()));
+            received.collect::<Vec<_>>()
+        };
+        assert_eq!(received(recording.events()), received(&events()));
+        let events = recording.events();
+        assert!(events.windows(2).all(|pair| pair[0].time <= pair[1].time));
+    }
+
+    #[test]
+    fn parsing_keeps_times_and_windows_and_skips_empty_lines() {
+        let lines: Vec<_> = events()
+            .iter()
+            .map(|event| ron::to_string(event).unwrap())
+            .collect();
+        let source = lines.join("\n\n") + "\n";
+
+        let recording = Recording::parse(&source).unwrap();
+        assert_eq!(recording.events(), events());
+    }
+
+    #[test]
+    fn parse_errors_report_the_line() {
+        let line = ron::to_string(&events()[0]).unwrap();
+        let source = format!("{line}\n\n(time: oops)\n");
+
+        match Recording::parse(&source) {
+            Err(RecordingError::Parse(line, _)) => assert_eq!(line, 3),
+            other => panic!("Expected a parse error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn replays_deliver_events_once_they_are_due() {
+        let mut replay = Replay::new(Recording { events: events() });
+        let start = replay.start;
+
+        assert_eq!(replay.take_due(start + Duration::from_millis(300)).len(), 2);
+        assert_eq!(
+            replay.next_deadline(),
+            Some(start + Duration::from_millis(500))
+        );
+        assert_eq!(replay.take_due(start + Duration::from_secs(1)).len(), 3);
+        assert_eq!(replay.next_deadline(), None);
+    }
+}
@@ -0,0 +1,178 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// A handle for scheduling callbacks on the application's event loop thread.
+///
+/// Callbacks run between events, when their deadline is reached. The event loop sleeps
+/// until the earliest deadline, so pending timers cost nothing while waiting.
+///
+/// Schedulers are cheap to clone; all clones share the same timers.
+#[derive(Clone, Default)]
+pub struct Scheduler {
+    queue: Rc<RefCell<TimerQueue>>,
+}
+
+/// An identifier of a timer scheduled with a [`Scheduler`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimerId(u64);
+
+/// A callback that only runs once a given delay has passed without it being triggered again.
+///
+/// Created with [`Scheduler::debounce`]. Debounced callbacks are cheap to clone;
+/// all clones share the same pending call.
+#[derive(Clone)]
+pub struct Debounced {
+    scheduler: Scheduler,
+    delay: Duration,
+    callback: Rc<RefCell<dyn FnMut()>>,
+    pending: Rc<Cell<Option<TimerId>>>,
+}
+
+#[derive(Default)]
+struct TimerQueue {
+    next_id: u64,
+    timers: HashMap<TimerId, Timer>,
+    /// The current time if it is controlled manually instead of following the system clock.
+    virtual_now: Option<Instant>,
+}
+
+struct Timer {
+    deadline: Instant,
+    callback: Callback,
+}
+
+enum Callback {
+    Once(Box<dyn FnOnce()>),
+    Repeating(Duration, Rc<RefCell<dyn FnMut()>>),
+}
+
+impl Scheduler {
+    /// Creates a scheduler without any timers.
+    pub fn new() -> Self {
+        Scheduler::default()
+    }
+
+    /// Runs `callback` once, after `delay`.
+    pub fn after(&self, delay: Duration, callback: impl FnOnce() + 'static) -> TimerId {
+        self.insert(self.now() + delay, Callback::Once(Box::new(callback)))
+    }
+
+    /// Runs `callback` every `interval`, starting one `interval` from now, until it is [cancelled](Scheduler::cancel).
+    pub fn every(&self, interval: Duration, callback: impl FnMut() + 'static) -> TimerId {
+        let callback = Callback::Repeating(interval, Rc::new(RefCell::new(callback)));
+        self.insert(self.now() + interval, callback)
+    }
+
+    /// Returns a [`Debounced`] callback that runs `delay` after it was last triggered.
+    pub fn debounce(&self, delay: Duration, callback: impl FnMut() + 'static) -> Debounced {
+        Debounced {
+            scheduler: self.clone(),
+            delay,
+            callback: Rc::new(RefCell::new(callback)),
+            pending: Rc::default(),
+        }
+    }
+
+    /// Cancels the timer with the given `id`. Cancelling a finished timer does nothing.
+    pub fn cancel(&self, id: TimerId) {
+        self.queue.borrow_mut().timers.remove(&id);
+    }
+
+    /// Returns the current time, according to which the timers are scheduled.
+    pub fn now(&self) -> Instant {
+        self.queue.borrow().virtual_now.unwrap_or_else(Instant::now)
+    }
+
+    /// Stops following the system clock and sets the current time to `now`,
+    /// so that timers can be run deterministically.
+    pub(super) fn set_virtual_time(&self, now: Instant) {
+        self.queue.borrow_mut().virtual_now = Some(now);
+    }
+
+    /// Returns the earliest deadline of all the timers.
+    pub(super) fn next_deadline(&self) -> Option<Instant> {
+        let queue = self.queue.borrow();
+        queue.timers.values().map(|timer| timer.deadline).min()
+    }
+
+    /// Runs the callbacks of the timers whose deadline is not later than `now`, in the order of their deadlines.
+    ///
+    /// Timers scheduled by the callbacks only run on the next call, even if they are already due.
+    pub(super) fn run_due(&self, now: Instant) {
+        let mut due: Vec<_> = {
+            let queue = self.queue.borrow();
+            let timers = queue.timers.iter();
+            timers
+                .filter(|(_, timer)| timer.deadline <= now)
+                .map(|(&id, timer)| (timer.deadline, id))
+                .collect()
+        };
+        due.sort_by_key(|&(deadline, _)| deadline);
+
+        for (_, id) in due {
+            let mut queue = self.queue.borrow_mut();
+            let timer = match queue.timers.get_mut(&id) {
+                Some(timer) => timer,
+                None => continue, // Cancelled by an earlier callback.
+            };
+            let repeating = match &timer.callback {
+                Callback::Once(_) => None,
+                Callback::Repeating(interval, callback) => Some((*interval, callback.clone())),
+            };
+            match repeating {
+                Some((interval, callback)) => {
+                    // Missed ticks are skipped instead of being run in a burst.
+                    timer.deadline += interval;
+                    if timer.deadline <= now {
+                        timer.deadline = now + interval;
+                    }
+                    drop(queue);
+                    (callback.borrow_mut())();
+                }
+                None => {
+                    let timer = queue.timers.remove(&id).unwrap();
+                    drop(queue);
+                    if let Callback::Once(callback) = timer.callback {
+                        callback();
+                    }
+                }
+            }
+        }
+    }
+
+    fn insert(&self, deadline: Instant, callback: Callback) -> TimerId {
+        let mut queue = self.queue.borrow_mut();
+        let id = TimerId(queue.next_id);
+        queue.next_id += 1;
+        queue.timers.insert(id, Timer { deadline, callback });
+        id
+    }
+}
+
+impl Debounced {
+    /// Schedules the callback to run after the delay, replacing any pending call.
+    pub fn trigger(&self) {
+        self.cancel();
+        let callback = self.callback.clone();
+        let pending = self.pending.clone();
+        let id = self.scheduler.after(self.delay, move || {
+            pending.set(None);
+            (callback.borrow_mut())();
+        });
+        self.pending.set(Some(id));
+    }
+
+    /// Cancels the pending call, if any.
+    pub fn cancel(&self) {
+        if let Some(id) = self.pending.take() {
+            self.scheduler.cancel(id);
+        }
+    }
+
+    /// Returns `true` if the callback is scheduled to run.
+    pub fn is_pending(&self) -> bool {
+        self.pending.get().is_some()
+    }
+}
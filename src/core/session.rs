@@ -0,0 +1,215 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use winit::dpi::{LogicalSize, PhysicalPosition, PhysicalSize};
+
+/// Name of the file a [`SessionStore`] saves the session to.
+const SESSION_FILE_NAME: &str = "session.ron";
+
+/// The smallest part of a restored window, in physical pixels, that has to be on a monitor
+/// so that it can still be grabbed and moved.
+const MIN_VISIBLE_SIZE: i32 = 64;
+
+/// An opt-in store that saves the application's windows and state when they are closed,
+/// and restores them when the application is [run](crate::core::Application::run).
+///
+/// The session is saved to `session.ron` in a directory named after the application
+/// in the XDG config directory (`$XDG_CONFIG_HOME`, or `~/.config` if it is not set).
+pub struct SessionStore {
+    path: Option<PathBuf>,
+    persisted_keys: Vec<String>,
+}
+
+/// The windows and state of an application saved by a [`SessionStore`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(super) struct Session {
+    /// The windows that were open, in the order they were opened.
+    pub(super) windows: Vec<WindowGeometry>,
+    /// The values of the [persisted](SessionStore::with_persisted_keys) keys in the application's store.
+    pub(super) state: HashMap<String, String>,
+}
+
+/// The position, size and state of a saved window.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(super) struct WindowGeometry {
+    /// The position of the window's top-left corner, including decorations, if the platform reports it.
+    pub(super) position: Option<PhysicalPosition<i32>>,
+    /// The size of the window's content.
+    pub(super) size: LogicalSize<f64>,
+    /// Whether the window was maximized.
+    pub(super) maximized: bool,
+    /// Whether the window was in borderless fullscreen.
+    pub(super) fullscreen: bool,
+}
+
+impl SessionStore {
+    /// Creates a session store for the application with the given name, which is used as the
+    /// name of its directory in the XDG config directory.
+    pub fn new(app_name: &str) -> Self {
+        let config_dir = env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+        if config_dir.is_none() {
+            eprintln!("Could not find the config directory, the session will not be saved");
+        }
+        SessionStore {
+            path: config_dir.map(|dir| dir.join(app_name).join(SESSION_FILE_NAME)),
+            persisted_keys: Vec::new(),
+        }
+    }
+
+    /// Saves the session to the file at `path` instead of the XDG config directory.
+    pub fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Also saves and restores the values of the given `keys` in the application's [`Store`](crate::ui::Store).
+    pub fn with_persisted_keys<S: Into<String>>(
+        mut self,
+        keys: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.persisted_keys = keys.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Returns the keys in the application's store whose values are saved.
+    pub(super) fn persisted_keys(&self) -> &[String] {
+        &self.persisted_keys
+    }
+
+    /// Loads the saved session, if there is one.
+    pub(super) fn load(&self) -> Option<Session> {
+        let path = self.path.as_ref()?;
+        let source = match fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return None,
+            Err(e) => {
+                eprintln!("Could not read {}: {e}", path.display());
+                return None;
+            }
+        };
+        ron::from_str(&source)
+            .map_err(|e| eprintln!("Invalid {}: {e}", path.display()))
+            .ok()
+    }
+
+    /// Saves the `session`, replacing the previously saved one.
+    pub(super) fn save(&self, session: &Session) {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return,
+        };
+        let result = ron::ser::to_string_pretty(session, Default::default())
+            .map_err(io::Error::other)
+            .and_then(|source| {
+                if let Some(dir) = path.parent() {
+                    fs::create_dir_all(dir)?;
+                }
+                fs::write(path, source)
+            });
+        if let Err(e) = result {
+            eprintln!("Could not save the session to {}: {e}", path.display());
+        }
+    }
+}
+
+/// Moves a window at `position` with the given outer `size` so that at least part of it is on one of the `monitors`,
+/// given by their positions and sizes, keeping it on the monitor it overlaps the most, and returns its new position.
+///
+/// Returns `None` if there are no monitors, so that the platform can place the window.
+pub(super) fn clamp_to_monitors(
+    position: PhysicalPosition<i32>,
+    size: PhysicalSize<u32>,
+    monitors: impl IntoIterator<Item = (PhysicalPosition<i32>, PhysicalSize<u32>)>,
+) -> Option<PhysicalPosition<i32>> {
+    let (width, height) = (size.width as i32, size.height as i32);
+    let overlap = |&(origin, extent): &(PhysicalPosition<i32>, PhysicalSize<u32>)| {
+        let overlap_x =
+            (position.x + width).min(origin.x + extent.width as i32) - position.x.max(origin.x);
+        let overlap_y =
+            (position.y + height).min(origin.y + extent.height as i32) - position.y.max(origin.y);
+        overlap_x.max(0) as i64 * overlap_y.max(0) as i64
+    };
+    let monitors: Vec<_> = monitors.into_iter().collect();
+    // Reversed, so that the first monitor (usually the primary one) wins if the window is on none of them.
+    let &(origin, extent) = monitors
+        .iter()
+        .rev()
+        .max_by_key(|monitor| overlap(monitor))?;
+
+    let (visible_width, visible_height) =
+        (width.min(MIN_VISIBLE_SIZE), height.min(MIN_VISIBLE_SIZE));
+    let x = position
+        .x
+        .min(origin.x + extent.width as i32 - visible_width)
+        .max(origin.x + visible_width - width);
+    // The title bar has to stay on the monitor, so the window can't stick out at the top.
+    let y = position
+        .y
+        .min(origin.y + extent.height as i32 - visible_height)
+        .max(origin.y);
+    Some(PhysicalPosition::new(x, y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONITORS: [(PhysicalPosition<i32>, PhysicalSize<u32>); 2] = [
+        (PhysicalPosition::new(0, 0), PhysicalSize::new(1920, 1080)),
+        (
+            PhysicalPosition::new(1920, 0),
+            PhysicalSize::new(1280, 1024),
+        ),
+    ];
+
+    fn clamp(x: i32, y: i32) -> Option<PhysicalPosition<i32>> {
+        clamp_to_monitors(
+            PhysicalPosition::new(x, y),
+            PhysicalSize::new(800, 600),
+            MONITORS,
+        )
+    }
+
+    #[test]
+    fn windows_on_a_monitor_stay_in_place() {
+        assert_eq!(clamp(100, 100), Some(PhysicalPosition::new(100, 100)));
+        assert_eq!(clamp(2000, 300), Some(PhysicalPosition::new(2000, 300)));
+    }
+
+    #[test]
+    fn windows_off_the_monitors_are_moved_back_to_the_one_they_overlap_the_most() {
+        // Mostly on the second monitor, but sticking out below it.
+        assert_eq!(clamp(2500, 1000), Some(PhysicalPosition::new(2500, 960)));
+        // Off the left edge of the first monitor, but still partly visible.
+        assert_eq!(clamp(-700, 100), Some(PhysicalPosition::new(-700, 100)));
+        assert_eq!(clamp(-790, 100), Some(PhysicalPosition::new(-736, 100)));
+    }
+
+    #[test]
+    fn windows_on_no_monitor_are_moved_to_the_first_one() {
+        assert_eq!(
+            clamp(10_000, 10_000),
+            Some(PhysicalPosition::new(1856, 1016))
+        );
+    }
+
+    #[test]
+    fn title_bars_stay_below_the_top_of_the_monitor() {
+        assert_eq!(clamp(100, -50), Some(PhysicalPosition::new(100, 0)));
+    }
+
+    #[test]
+    fn windows_are_left_to_the_platform_without_monitors() {
+        let position = PhysicalPosition::new(100, 100);
+        assert_eq!(
+            clamp_to_monitors(position, PhysicalSize::new(800, 600), []),
+            None
+        );
+    }
+}
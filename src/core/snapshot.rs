@@ -0,0 +1,218 @@
+use crate::core::pixels::{read_rgba, rgba_info};
+use crate::core::*;
+use crate::ui::Widget;
+
+use skia_safe::{images, surfaces, Color, Data, EncodedImageFormat, ISize, Image};
+use std::fmt::{self, Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::{env, fs, io};
+use winit::dpi::PhysicalSize;
+
+/// The environment variable that makes [`Snapshot`] overwrite the golden images instead of comparing to them.
+pub const UPDATE_GOLDENS_VAR: &str = "UPDATE_GOLDENS";
+
+/// Renders drawing code or widget trees on the CPU and compares the result to golden PNG images.
+///
+/// When a rendering does not match its golden image, the rendering and an image highlighting
+/// the differing pixels in red are written next to the golden image, with `.actual.png` and `.diff.png` extensions.
+/// Running with the [`UPDATE_GOLDENS_VAR`] environment variable set to `1` writes the renderings as the new golden images.
+pub struct Snapshot {
+    size: ISize,
+    scale_factor: f32,
+    tolerance: u8,
+}
+
+/// An error that occurred while comparing a rendering to its golden image.
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// The golden image could not be read, or the rendering or diff image could not be written.
+    Io(PathBuf, io::Error),
+    /// The golden image in the given file could not be decoded.
+    InvalidImage(PathBuf),
+    /// The golden image has a different size than the rendering.
+    SizeMismatch {
+        /// The size of the golden image.
+        expected: ISize,
+        /// The size of the rendering.
+        actual: ISize,
+    },
+    /// Some pixels differ from the golden image by more than the tolerance.
+    Mismatch {
+        /// The number of differing pixels.
+        pixels: usize,
+        /// The image highlighting the differing pixels.
+        diff: PathBuf,
+    },
+}
+
+impl Snapshot {
+    /// Creates a snapshot of the given size, in physical pixels, with a scale factor of 1 and no tolerance.
+    pub fn new(width: i32, height: i32) -> Self {
+        Snapshot {
+            size: ISize::new(width.max(1), height.max(1)),
+            scale_factor: 1.0,
+            tolerance: 0,
+        }
+    }
+
+    /// Sets the scale factor used to map logical pixels to physical pixels.
+    pub fn with_scale_factor(mut self, scale_factor: f32) -> Self {
+        self.scale_factor = scale_factor;
+        self
+    }
+
+    /// Sets how much each color channel of a pixel may differ from the golden image,
+    /// to allow for small differences in anti-aliasing between platforms.
+    pub fn with_tolerance(mut self, tolerance: u8) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Renders `drawing` on a white background, in logical pixels.
+    pub fn render(&self, drawing: impl FnOnce(&DrawContext)) -> Image {
+        let mut surface =
+            surfaces::raster_n32_premul(self.size).expect("Could not create raster surface");
+        let canvas = surface.canvas();
+        canvas.clear(Color::WHITE);
+        canvas.scale((self.scale_factor, self.scale_factor));
+        let size = PhysicalSize::new(self.size.width, self.size.height)
+            .to_logical(self.scale_factor as f64);
+        drawing(&DrawContext::new(canvas, self.scale_factor, size));
+        surface.image_snapshot()
+    }
+
+    /// Lays out and renders the widget tree starting at `root`, the way a window would.
+    pub fn render_ui(&self, root: Widget) -> Image {
+        let size = (self.size.width as u32, self.size.height as u32).into();
+        let mut harness = Harness::new(size);
+        harness.set_scale_factor(self.scale_factor.into());
+        harness.set_ui(root);
+        harness.snapshot()
+    }
+
+    /// Renders `drawing` and compares it to the golden image at `golden`.
+    pub fn check(
+        &self,
+        golden: impl AsRef<Path>,
+        drawing: impl FnOnce(&DrawContext),
+    ) -> Result<(), SnapshotError> {
+        self.compare(&self.render(drawing), golden.as_ref())
+    }
+
+    /// Renders the widget tree starting at `root` and compares it to the golden image at `golden`.
+    pub fn check_ui(&self, golden: impl AsRef<Path>, root: Widget) -> Result<(), SnapshotError> {
+        self.compare(&self.render_ui(root), golden.as_ref())
+    }
+
+    /// Renders `drawing` and panics if it does not match the golden image at `golden`.
+    pub fn assert(&self, golden: impl AsRef<Path>, drawing: impl FnOnce(&DrawContext)) {
+        if let Err(e) = self.check(golden.as_ref(), drawing) {
+            panic!("Snapshot {} failed: {e}", golden.as_ref().display());
+        }
+    }
+
+    /// Renders the widget tree starting at `root` and panics if it does not match the golden image at `golden`.
+    pub fn assert_ui(&self, golden: impl AsRef<Path>, root: Widget) {
+        if let Err(e) = self.check_ui(golden.as_ref(), root) {
+            panic!("Snapshot {} failed: {e}", golden.as_ref().display());
+        }
+    }
+
+    /// Compares `image` to the golden image at `golden`, or replaces the golden image if requested.
+    fn compare(&self, image: &Image, golden: &Path) -> Result<(), SnapshotError> {
+        let actual = read_rgba(image).expect("Could not read the image's pixels");
+        if env::var(UPDATE_GOLDENS_VAR).is_ok_and(|value| value == "1") {
+            if let Some(parent) = golden.parent() {
+                fs::create_dir_all(parent).map_err(|e| SnapshotError::Io(parent.into(), e))?;
+            }
+            return write_png(golden, image.dimensions(), &actual);
+        }
+
+        let data = fs::read(golden).map_err(|e| SnapshotError::Io(golden.into(), e))?;
+        let expected_image = Image::from_encoded(Data::new_copy(&data))
+            .ok_or_else(|| SnapshotError::InvalidImage(golden.into()))?;
+        if expected_image.dimensions() != image.dimensions() {
+            write_png(
+                &golden.with_extension("actual.png"),
+                image.dimensions(),
+                &actual,
+            )?;
+            return Err(SnapshotError::SizeMismatch {
+                expected: expected_image.dimensions(),
+                actual: image.dimensions(),
+            });
+        }
+        let expected = read_rgba(&expected_image).expect("Could not read the image's pixels");
+
+        let mut diff = Vec::with_capacity(actual.len());
+        let mut mismatched = 0;
+        for (actual, expected) in actual.chunks_exact(4).zip(expected.chunks_exact(4)) {
+            let matches = actual
+                .iter()
+                .zip(expected)
+                .all(|(a, e)| a.abs_diff(*e) <= self.tolerance);
+            if matches {
+                // Matching pixels are shown faded, so that the differences stand out.
+                let luma =
+                    (expected[0] as u32 * 3 + expected[1] as u32 * 6 + expected[2] as u32) / 10;
+                let faded = (255 - (255 - luma) / 4) as u8;
+                diff.extend([faded, faded, faded, 255]);
+            } else {
+                mismatched += 1;
+                diff.extend([255, 0, 0, 255]);
+            }
+        }
+        if mismatched == 0 {
+            return Ok(());
+        }
+
+        let diff_path = golden.with_extension("diff.png");
+        write_png(
+            &golden.with_extension("actual.png"),
+            image.dimensions(),
+            &actual,
+        )?;
+        write_png(&diff_path, image.dimensions(), &diff)?;
+        Err(SnapshotError::Mismatch {
+            pixels: mismatched,
+            diff: diff_path,
+        })
+    }
+}
+
+/// Encodes the unpremultiplied RGBA `pixels` as a PNG file at `path`.
+fn write_png(path: &Path, size: ISize, pixels: &[u8]) -> Result<(), SnapshotError> {
+    let info = rgba_info(size);
+    let data = images::raster_from_data(&info, Data::new_copy(pixels), info.min_row_bytes())
+        .and_then(|image| image.encode(None, EncodedImageFormat::PNG, None))
+        .ok_or_else(|| SnapshotError::InvalidImage(path.into()))?;
+    fs::write(path, data.as_bytes()).map_err(|e| SnapshotError::Io(path.into(), e))
+}
+
+impl Display for SnapshotError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Io(path, e) => write!(f, "Could not access {}: {e}", path.display()),
+            SnapshotError::InvalidImage(path) => {
+                write!(f, "Could not encode or decode {}", path.display())
+            }
+            SnapshotError::SizeMismatch { expected, actual } => write!(
+                f,
+                "Expected a {}x{} image, got {}x{}",
+                expected.width, expected.height, actual.width, actual.height
+            ),
+            SnapshotError::Mismatch { pixels, diff } => {
+                write!(f, "{pixels} pixels differ, see {}", diff.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SnapshotError::Io(_, e) => Some(e),
+            _ => None,
+        }
+    }
+}
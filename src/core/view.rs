@@ -0,0 +1,772 @@
+use crate::core::drag::DragStart;
+use crate::core::file_drop::FileDrag;
+use crate::core::gesture::{GestureTracker, Recognized};
+use crate::core::{FileDrop, Gesture, Input, TouchPoint};
+use crate::ui::{
+    self, DragPayload, GestureKind, GestureRecognizer, HitRegion, ResizeEdge, TextInput, Viewport,
+    Widget, WidgetKey,
+};
+
+use skia_safe::{Canvas, Color4f, Point, Rect, Vector};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use winit::dpi::LogicalPosition;
+use winit::event::{ElementState, MouseButton, TouchPhase};
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+use winit::window::WindowId;
+
+/// Longest time between two clicks of a double-click.
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(500);
+
+/// Farthest distance, in logical pixels, between two clicks of a double-click.
+const DOUBLE_CLICK_DISTANCE: f32 = 4.0;
+
+/// Distance, in logical pixels, the cursor has to move with the button pressed to start dragging a widget.
+const DRAG_DISTANCE: f32 = 4.0;
+
+/// Horizontal distance, in logical pixels, from the point pressed with the right button
+/// to each of the two fingers it emulates.
+const EMULATED_FINGER_OFFSET: f32 = 40.0;
+
+/// Zoom change of viewports per logical pixel scrolled with the mouse wheel, as a power of e.
+const WHEEL_ZOOM_SPEED: f32 = 0.01;
+
+/// What a window should do in response to an input to its [`View`].
+#[derive(Debug, Clone)]
+pub(super) enum Response {
+    /// A widget's action has been triggered.
+    Action(String),
+    /// A [caption](HitRegion::Caption) has been pressed, so the window should be moved with the cursor.
+    DragMove,
+    /// A [resize border](HitRegion::Resize) has been pressed, so the window should be resized with the cursor.
+    DragResize(ResizeEdge),
+    /// A [caption](HitRegion::Caption) has been double-clicked.
+    ToggleMaximized,
+    /// Files have been dropped on a drop target.
+    FilesDropped(FileDrop),
+    /// A [draggable](Widget::with_drag_payload) widget has started being dragged.
+    DragStarted(Box<DragStart>),
+    /// Gestures have been recognized, changed or ended by a widget's recognizers.
+    Gestures(Vec<Gesture>),
+    /// Text has been copied or cut from a [text input](ui::WidgetKind::TextInput), to be written to the clipboard.
+    CopyText(String),
+    /// The text in the clipboard should be [pasted](View::paste) into the focused text input.
+    PasteText,
+}
+
+/// A mouse button pressed to [emulate touches](View::set_touch_emulation).
+#[derive(Debug, Clone, Copy)]
+struct EmulatedTouch {
+    button: MouseButton,
+    press: Point,
+}
+
+/// The widget tree of a window, along with the state needed to lay it out, paint it
+/// and dispatch input to it, independently of how the window is presented.
+#[derive(Default)]
+pub(super) struct View {
+    root: Option<Widget>,
+    cursor_position: Option<Point>,
+    bounds: Rect,
+    resize_border: f32,
+    last_caption_press: Option<(Instant, Point)>,
+    file_drag: FileDrag,
+    drag_press: Option<Point>,
+    dragging: bool,
+    gestures: GestureTracker,
+    gesture_target: Option<(Vec<GestureRecognizer>, Option<String>)>,
+    touch_emulation: bool,
+    emulated_touch: Option<EmulatedTouch>,
+    space_held: bool,
+    /// The viewport panned with the mouse and the last position of the cursor.
+    viewport_pan: Option<(WidgetKey, Point)>,
+    /// The viewport panned and zoomed with fingers, its state before, and where the first finger touched it.
+    touch_viewport: Option<(WidgetKey, Viewport, Point)>,
+    /// The text input with the keyboard focus.
+    focused: Option<WidgetKey>,
+    modifiers: ModifiersState,
+}
+
+impl View {
+    /// Returns the root of the widget tree, if there is one.
+    pub(super) fn root(&self) -> Option<&Widget> {
+        self.root.as_ref()
+    }
+
+    /// Returns a mutable reference to the root of the widget tree, if there is one.
+    pub(super) fn root_mut(&mut self) -> Option<&mut Widget> {
+        self.root.as_mut()
+    }
+
+    /// Replaces the widget tree with the one starting at `root`.
+    pub(super) fn set_root(&mut self, root: Option<Widget>) {
+        self.root = root;
+        self.focused = None;
+    }
+
+    /// Sets the width of the border along the view's edges that resizes the window when dragged,
+    /// or `0.0` if the OS draws the window's borders.
+    pub(super) fn set_resize_border(&mut self, width: f32) {
+        self.resize_border = width;
+    }
+
+    /// Returns the part of the window at the `point`: the [resize border](View::set_resize_border)
+    /// or the region of the widget there.
+    pub(super) fn region_at(&self, point: Point) -> HitRegion {
+        if let Some(edge) = ResizeEdge::at(self.bounds, point, self.resize_border) {
+            return HitRegion::Resize(edge);
+        }
+        self.root
+            .as_ref()
+            .and_then(|root| root.region_at(point))
+            .unwrap_or(HitRegion::Client)
+    }
+
+    /// Returns the fingers touching the view.
+    pub(super) fn touches(&self) -> Vec<TouchPoint> {
+        self.gestures.touches()
+    }
+
+    /// Sets whether the left mouse button emulates a finger touching the view, and the right one
+    /// two fingers on either side of where it was pressed, to pinch and rotate by moving the cursor.
+    pub(super) fn set_touch_emulation(&mut self, emulation: bool) {
+        self.touch_emulation = emulation;
+        self.emulated_touch = None;
+    }
+
+    /// Returns `true` if the mouse [emulates touches](View::set_touch_emulation).
+    pub(super) fn touch_emulation(&self) -> bool {
+        self.touch_emulation
+    }
+
+    /// Updates the view with an `input` received at `now` and returns what the window should do in response, if anything.
+    pub(super) fn handle_input(&mut self, input: &Input, now: Instant) -> Option<Response> {
+        if self.touch_emulation {
+            if let Some(touches) = self.emulate_touches(input) {
+                return self.handle_touches(touches, now);
+            }
+        }
+        match input {
+            Input::Touch {
+                id,
+                phase,
+                position,
+            } => {
+                let position = Point::new(position.x, position.y);
+                return self.handle_touches(vec![(*id, *phase, position)], now);
+            }
+            Input::PinchGesture { delta, phase } => {
+                let scale = 1.0 + delta;
+                let response =
+                    self.handle_touchpad(GestureKind::Pinch, *phase, Vector::default(), scale, 0.0);
+                // Pinches not recognized by a widget zoom the viewport under the cursor.
+                if response.is_none() {
+                    self.zoom_viewport(scale);
+                }
+                return response;
+            }
+            Input::Wheel { delta_y, .. } => self.zoom_viewport((-delta_y * WHEEL_ZOOM_SPEED).exp()),
+            Input::Modifiers(modifiers) => self.modifiers = *modifiers,
+            Input::Key {
+                key,
+                state: ElementState::Pressed,
+                text,
+                ..
+            } if self.focused.is_some() => return self.edit_text(key, text.as_deref()),
+            Input::Text(text) => self.paste(text),
+            Input::Key {
+                key: Key::Named(NamedKey::Space),
+                state,
+                ..
+            } => self.space_held = *state == ElementState::Pressed,
+            Input::PanGesture {
+                delta_x,
+                delta_y,
+                phase,
+            } => {
+                let translation = Vector::new(*delta_x, *delta_y);
+                return self.handle_touchpad(GestureKind::Pan, *phase, translation, 1.0, 0.0);
+            }
+            Input::RotationGesture { delta, phase } => {
+                // Touchpads turn counterclockwise with positive degrees, gestures clockwise with positive radians.
+                let rotation = -delta.to_radians();
+                return self.handle_touchpad(
+                    GestureKind::Rotate,
+                    *phase,
+                    Vector::default(),
+                    1.0,
+                    rotation,
+                );
+            }
+            Input::DoubleTapGesture => {
+                return self.handle_touchpad(
+                    GestureKind::DoubleTap,
+                    TouchPhase::Ended,
+                    Vector::default(),
+                    1.0,
+                    0.0,
+                );
+            }
+            Input::CursorMoved(position) => {
+                let position = Point::new(position.x, position.y);
+                self.cursor_position = Some(position);
+                if let Some((key, last_position)) = self.viewport_pan {
+                    self.viewport_pan = Some((key, position));
+                    self.update_viewport(key, |viewport| viewport.pan_by(position - last_position));
+                    return None;
+                }
+                if !self.file_drag.hovered().is_empty() {
+                    self.highlight_drop_target();
+                }
+                return self
+                    .start_drag(position)
+                    .map(|start| Response::DragStarted(Box::new(start)));
+            }
+            Input::FileHovered(path) => {
+                self.file_drag.hover(path.clone());
+                self.highlight_drop_target();
+            }
+            Input::FileHoverCancelled => {
+                self.file_drag.cancel();
+                self.highlight_drop_target();
+            }
+            Input::FileDropped(path) => {
+                let paths = self.file_drag.drop_file(path.clone())?;
+                self.highlight_drop_target();
+                return self.drop_files(paths).map(Response::FilesDropped);
+            }
+            Input::CursorLeft => self.cursor_position = None,
+            Input::MouseButton {
+                button: MouseButton::Middle,
+                state: ElementState::Pressed,
+            } => {
+                self.start_viewport_pan(true);
+            }
+            Input::MouseButton {
+                button: MouseButton::Left | MouseButton::Middle,
+                state: ElementState::Released,
+            } if self.viewport_pan.is_some() => {
+                // Panning a viewport doesn't click what is under the cursor.
+                self.viewport_pan = None;
+            }
+            Input::MouseButton {
+                button: MouseButton::Left,
+                state: ElementState::Pressed,
+            } => {
+                let position = self.cursor_position?;
+                if self.start_viewport_pan(self.space_held) {
+                    return None;
+                }
+                self.focus_text_input_at(position);
+                self.drag_press = self
+                    .root
+                    .as_ref()
+                    .and_then(|root| root.drag_source_at(position))
+                    .map(|_| position);
+                return match self.region_at(position) {
+                    HitRegion::Client | HitRegion::PassThrough => None,
+                    HitRegion::Resize(edge) => Some(Response::DragResize(edge)),
+                    HitRegion::Caption => {
+                        let double_click =
+                            self.last_caption_press
+                                .take()
+                                .is_some_and(|(time, last_position)| {
+                                    now - time <= DOUBLE_CLICK_TIME
+                                        && (position - last_position).length()
+                                            <= DOUBLE_CLICK_DISTANCE
+                                });
+                        if double_click {
+                            Some(Response::ToggleMaximized)
+                        } else {
+                            self.last_caption_press = Some((now, position));
+                            Some(Response::DragMove)
+                        }
+                    }
+                };
+            }
+            Input::MouseButton {
+                button: MouseButton::Left,
+                state: ElementState::Released,
+            } => {
+                self.drag_press = None;
+                // Dropping a dragged widget doesn't click what is under it.
+                if std::mem::take(&mut self.dragging) {
+                    return None;
+                }
+                let root = self.root.as_ref()?;
+                let action = root.action_at(self.cursor_position?)?;
+                return Some(Response::Action(action.to_owned()));
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Returns `true` if a [text input](ui::WidgetKind::TextInput) has the keyboard focus.
+    pub(super) fn is_editing_text(&self) -> bool {
+        self.focused.is_some()
+    }
+
+    /// Gives the keyboard focus to the text input at `position`, moving its cursor there,
+    /// or removes it if there is none. The selection is extended while Shift is held.
+    fn focus_text_input_at(&mut self, position: Point) {
+        let root = match &self.root {
+            Some(root) => root,
+            None => return,
+        };
+        let target = root.text_input_at(position).and_then(|widget| {
+            // Text inputs inside viewports are laid out in content coordinates.
+            let (bounds, window_bounds) = (widget.bounds(), root.window_bounds(widget.key())?);
+            let x = bounds.left
+                + (position.x - window_bounds.left) * bounds.width() / window_bounds.width();
+            Some((widget.key(), widget.text_position_at(x)?))
+        });
+        self.focus(target.map(|(key, _)| key));
+        if let Some((_, text_position)) = target {
+            let select = self.modifiers.shift_key();
+            self.update_focused_text(|input| input.set_cursor(text_position, select));
+        }
+    }
+
+    /// Gives the keyboard focus to the text input with the given `key`, or removes it with `None`.
+    fn focus(&mut self, key: Option<WidgetKey>) {
+        self.focused = key;
+        if let Some(root) = &mut self.root {
+            root.focus(key);
+        }
+    }
+
+    /// Edits the focused text input with a pressed `key` producing the given `text`, and returns
+    /// the text to copy to the clipboard, or a request to paste into it, for the editing shortcuts.
+    fn edit_text(&mut self, key: &Key, text: Option<&str>) -> Option<Response> {
+        let shortcut = self.modifiers.control_key() || self.modifiers.super_key();
+        let select = self.modifiers.shift_key();
+        if let (Key::Character(c), true) = (key, shortcut) {
+            return match c.to_lowercase().as_str() {
+                "a" => {
+                    self.update_focused_text(TextInput::select_all);
+                    None
+                }
+                "c" => self
+                    .update_focused_text(|input| input.selected_text().map(str::to_owned))?
+                    .map(Response::CopyText),
+                "x" => self
+                    .update_focused_text(TextInput::cut)?
+                    .map(Response::CopyText),
+                "v" => Some(Response::PasteText),
+                _ => None,
+            };
+        }
+        match key {
+            Key::Named(NamedKey::ArrowLeft) => {
+                self.update_focused_text(|input| input.move_left(select));
+            }
+            Key::Named(NamedKey::ArrowRight) => {
+                self.update_focused_text(|input| input.move_right(select));
+            }
+            Key::Named(NamedKey::Home) => {
+                self.update_focused_text(|input| input.move_home(select));
+            }
+            Key::Named(NamedKey::End) => {
+                self.update_focused_text(|input| input.move_end(select));
+            }
+            Key::Named(NamedKey::Backspace) => {
+                self.update_focused_text(TextInput::delete_backward);
+            }
+            Key::Named(NamedKey::Delete) => {
+                self.update_focused_text(TextInput::delete_forward);
+            }
+            Key::Named(NamedKey::Escape) => self.focus(None),
+            _ if !shortcut => {
+                if let Some(text) = text {
+                    self.paste(text);
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Inserts `text` into the focused text input, replacing its selection.
+    pub(super) fn paste(&mut self, text: &str) {
+        self.update_focused_text(|input| input.insert(text));
+    }
+
+    /// Changes the text or selection of the focused text input with `edit`, if there is one.
+    fn update_focused_text<R>(&mut self, edit: impl FnOnce(&mut TextInput) -> R) -> Option<R> {
+        let key = self.focused?;
+        self.root.as_mut()?.find_key_mut(key)?.edit_text(edit)
+    }
+
+    /// Starts panning the viewport under the cursor with the mouse, if the press is on the viewport's background
+    /// or `anywhere` in it, and returns `true` if it has started.
+    fn start_viewport_pan(&mut self, anywhere: bool) -> bool {
+        let (root, position) = match (&self.root, self.cursor_position) {
+            (Some(root), Some(position)) => (root, position),
+            _ => return false,
+        };
+        if self.region_at(position) != HitRegion::Client {
+            return false;
+        }
+        let viewport = match root.viewport_at(position) {
+            Some(viewport) => viewport,
+            None => return false,
+        };
+        let background =
+            root.action_at(position).is_none() && root.drag_source_at(position).is_none();
+        if anywhere || background {
+            self.viewport_pan = Some((viewport.key(), position));
+        }
+        self.viewport_pan.is_some()
+    }
+
+    /// Zooms the viewport under the cursor by `factor`, around the cursor.
+    fn zoom_viewport(&mut self, factor: f32) {
+        let position = match self.cursor_position {
+            Some(position) => position,
+            None => return,
+        };
+        let key = self
+            .root
+            .as_ref()
+            .and_then(|root| root.viewport_at(position))
+            .map(Widget::key);
+        if let Some(key) = key {
+            self.update_viewport(key, |viewport| viewport.zoom_by(factor, position));
+        }
+    }
+
+    /// Scrolls the viewport at `position` by `delta`, in window pixels, towards the end of its content
+    /// for positive deltas, and returns `true` if there is a viewport to scroll.
+    pub(super) fn scroll_viewport_at(&mut self, position: Point, delta: Vector) -> bool {
+        let key = self
+            .root
+            .as_ref()
+            .and_then(|root| root.viewport_at(position))
+            .map(Widget::key);
+        if let Some(key) = key {
+            self.update_viewport(key, |viewport| viewport.pan_by(-delta));
+        }
+        key.is_some()
+    }
+
+    /// Pans and zooms the viewport touched by the fingers, if they don't make a gesture recognized by a widget,
+    /// so that the content under the first finger follows the fingers' center.
+    fn follow_touches(&mut self) {
+        let (key, start, anchor) = match self.touch_viewport {
+            Some(touch_viewport) => touch_viewport,
+            None => return,
+        };
+        let (translation, scale) = (self.gestures.translation(), self.gestures.scale());
+        self.update_viewport(key, |viewport| {
+            *viewport = start;
+            viewport.set_zoom(start.zoom() * scale, anchor);
+            viewport.pan_by(translation);
+        });
+        if self.gestures.is_idle() {
+            self.touch_viewport = None;
+        }
+    }
+
+    /// Changes the pan and zoom of the viewport with the given `key` with `update`.
+    fn update_viewport(&mut self, key: WidgetKey, update: impl FnOnce(&mut Viewport)) {
+        if let Some(widget) = self.root.as_mut().and_then(|root| root.find_key_mut(key)) {
+            widget.update_viewport(update);
+        }
+    }
+
+    /// Converts mouse inputs to the touches they [emulate](View::set_touch_emulation), if they emulate any.
+    ///
+    /// Presses on captions and resize borders still move and resize the window.
+    fn emulate_touches(&mut self, input: &Input) -> Option<Vec<(u64, TouchPhase, Point)>> {
+        let phase = match input {
+            Input::MouseButton {
+                button: button @ (MouseButton::Left | MouseButton::Right),
+                state: ElementState::Pressed,
+            } if self.emulated_touch.is_none() => {
+                let press = self.cursor_position?;
+                if !matches!(
+                    self.region_at(press),
+                    HitRegion::Client | HitRegion::PassThrough
+                ) {
+                    return None;
+                }
+                self.emulated_touch = Some(EmulatedTouch {
+                    button: *button,
+                    press,
+                });
+                TouchPhase::Started
+            }
+            Input::CursorMoved(position) => {
+                self.cursor_position = Some(Point::new(position.x, position.y));
+                self.emulated_touch?;
+                TouchPhase::Moved
+            }
+            Input::MouseButton {
+                button,
+                state: ElementState::Released,
+            } if self
+                .emulated_touch
+                .is_some_and(|touch| touch.button == *button) =>
+            {
+                TouchPhase::Ended
+            }
+            _ => return None,
+        };
+        let touch = self.emulated_touch?;
+        let cursor = self.cursor_position.unwrap_or(touch.press);
+        if phase == TouchPhase::Ended {
+            self.emulated_touch = None;
+        }
+        if touch.button == MouseButton::Left {
+            return Some(vec![(0, phase, cursor)]);
+        }
+        let offset = Vector::new(EMULATED_FINGER_OFFSET, 0.0) + (cursor - touch.press);
+        Some(vec![
+            (0, phase, touch.press + offset),
+            (1, phase, touch.press - offset),
+        ])
+    }
+
+    /// Recognizes gestures from fingers touching, moving on or leaving the view at `now`.
+    ///
+    /// Taps on widgets that don't recognize them trigger the action of the widget under the finger, like clicks.
+    fn handle_touches(
+        &mut self,
+        touches: Vec<(u64, TouchPhase, Point)>,
+        now: Instant,
+    ) -> Option<Response> {
+        let mut gestures = Vec::new();
+        let mut action = None;
+        for (id, phase, position) in touches {
+            if phase == TouchPhase::Started && self.gestures.is_idle() {
+                self.gesture_target = self.gesture_target_at(position);
+                self.touch_viewport = self
+                    .root
+                    .as_ref()
+                    .filter(|_| self.gesture_target.is_none())
+                    .and_then(|root| root.viewport_at(position))
+                    .and_then(|widget| Some((widget.key(), *widget.viewport()?, position)));
+            }
+            for recognized in self.gestures.touch(id, phase, position, now) {
+                if recognized.kind == GestureKind::Tap
+                    && self.recognizer(GestureKind::Tap).is_none()
+                {
+                    action = self
+                        .root
+                        .as_ref()
+                        .and_then(|root| root.action_at(recognized.position))
+                        .map(str::to_owned);
+                }
+                gestures.extend(self.gesture(recognized));
+            }
+            self.follow_touches();
+        }
+        if !gestures.is_empty() {
+            return Some(Response::Gestures(gestures));
+        }
+        action.map(Response::Action)
+    }
+
+    /// Recognizes a gesture made on a touchpad at the cursor's position, with a `translation`,
+    /// a `scale` and a `rotation` relative to the last update.
+    fn handle_touchpad(
+        &mut self,
+        kind: GestureKind,
+        phase: TouchPhase,
+        translation: Vector,
+        scale: f32,
+        rotation: f32,
+    ) -> Option<Response> {
+        let position = self.cursor_position?;
+        if (self.gestures.is_idle() && phase == TouchPhase::Started)
+            || kind == GestureKind::DoubleTap
+        {
+            self.gesture_target = self.gesture_target_at(position);
+        }
+        let recognized =
+            self.gestures
+                .touchpad(kind, phase, position, translation, scale, rotation);
+        let gesture = self.gesture(recognized)?;
+        Some(Response::Gestures(vec![gesture]))
+    }
+
+    /// Recognizes a long press if a finger has been held without moving until `now`.
+    pub(super) fn poll_gestures(&mut self, now: Instant) -> Option<Gesture> {
+        let recognized = self.gestures.poll(now)?;
+        self.gesture(recognized)
+    }
+
+    /// Returns when a finger held without moving will be long pressing, if one is.
+    pub(super) fn next_gesture_deadline(&self) -> Option<Instant> {
+        self.gestures.long_press_deadline()
+    }
+
+    /// Returns the recognizers and ID of the deepest widget recognizing gestures at `position`.
+    fn gesture_target_at(
+        &self,
+        position: Point,
+    ) -> Option<(Vec<GestureRecognizer>, Option<String>)> {
+        let widget = self.root.as_ref()?.gesture_target_at(position)?;
+        Some((widget.gestures().to_vec(), widget.id().map(str::to_owned)))
+    }
+
+    /// Returns the recognizer of the given `kind` of the widget the gesture started on, if it has one.
+    fn recognizer(&self, kind: GestureKind) -> Option<&GestureRecognizer> {
+        let (recognizers, _) = self.gesture_target.as_ref()?;
+        recognizers
+            .iter()
+            .find(|recognizer| recognizer.kind == kind)
+    }
+
+    /// Returns the `recognized` gesture, if the widget it started on recognizes it.
+    fn gesture(&self, recognized: Recognized) -> Option<Gesture> {
+        let recognizer = self.recognizer(recognized.kind)?;
+        let target_id = self.gesture_target.as_ref()?.1.clone();
+        Some(Gesture {
+            action: recognizer.action.clone(),
+            target_id,
+            kind: recognized.kind,
+            phase: recognized.phase,
+            position: LogicalPosition::new(recognized.position.x, recognized.position.y),
+            translation: self.gestures.translation(),
+            scale: self.gestures.scale(),
+            rotation: self.gestures.rotation(),
+        })
+    }
+
+    /// Starts dragging the widget pressed before the cursor moved to `position`,
+    /// once the cursor is far enough from where it was pressed.
+    fn start_drag(&mut self, position: Point) -> Option<DragStart> {
+        let press = self.drag_press?;
+        if self.dragging || (position - press).length() < DRAG_DISTANCE {
+            return None;
+        }
+        let root = self.root.as_ref()?;
+        let widget = root.drag_source_at(press)?;
+        let bounds = root.window_bounds(widget.key())?;
+        self.dragging = true;
+        Some(DragStart {
+            widget: widget.clone(),
+            bounds,
+            grab: press - Point::new(bounds.left, bounds.top),
+        })
+    }
+
+    /// Highlights the drop target at `position` if it accepts the dragged `payload`,
+    /// and returns its action and ID. A `position` of `None` removes the highlight.
+    pub(super) fn drag_over(
+        &mut self,
+        position: Option<Point>,
+        payload: &DragPayload,
+    ) -> Option<(String, Option<String>)> {
+        let root = self.root.as_mut()?;
+        let target = position
+            .and_then(|position| root.drop_target_at(position))
+            .and_then(|widget| {
+                let drop_target = widget.drop_target()?;
+                drop_target.accepts_payload(payload).then(|| {
+                    let target = (drop_target.action.clone(), widget.id().map(str::to_owned));
+                    (widget.key(), target)
+                })
+            });
+        let (key, target) = target.unzip();
+        root.highlight_drop_target(key);
+        target
+    }
+
+    /// Highlights the drop target under the cursor if it accepts any of the hovering files.
+    ///
+    /// The OS does not report the cursor's position while dragging files over every platform's windows,
+    /// so the last known position is used.
+    fn highlight_drop_target(&mut self) {
+        let hovered = self.file_drag.hovered();
+        let root = match &mut self.root {
+            Some(root) => root,
+            None => return,
+        };
+        let key = self
+            .cursor_position
+            .and_then(|position| root.drop_target_at(position))
+            .filter(|widget| {
+                widget
+                    .drop_target()
+                    .is_some_and(|target| hovered.iter().any(|path| target.accepts(path)))
+            })
+            .map(Widget::key);
+        root.highlight_drop_target(key);
+    }
+
+    /// Returns the drop of the `paths` on the drop target under the cursor, if it accepts any of them.
+    fn drop_files(&self, paths: Vec<PathBuf>) -> Option<FileDrop> {
+        let position = self.cursor_position?;
+        let drop_target = self
+            .root
+            .as_ref()?
+            .drop_target_at(position)?
+            .drop_target()?;
+        let paths: Vec<_> = paths
+            .into_iter()
+            .filter(|path| drop_target.accepts(path))
+            .collect();
+        if paths.is_empty() {
+            return None;
+        }
+        Some(FileDrop {
+            action: drop_target.action.clone(),
+            paths,
+            position: LogicalPosition::new(position.x, position.y),
+        })
+    }
+
+    /// Marks the widgets with the given `keys` as dirty.
+    pub(super) fn invalidate(&mut self, keys: &HashSet<WidgetKey>) {
+        if let Some(root) = &mut self.root {
+            root.mark_dirty(keys);
+        }
+    }
+
+    /// Lays out the widget tree to fill the `bounds` and paints it, along with an `error` on top, if any.
+    ///
+    /// Only the area damaged since the last call is painted, unless a `full_redraw` is requested
+    /// or there is an error. `present` is called with the damaged area and the instructions that
+    /// paint it, and is not called at all if nothing has changed. All the [`Signal`](ui::Signal)s
+    /// read while painting subscribe the window with the given `window_id` to their changes.
+    pub(super) fn render(
+        &mut self,
+        window_id: WindowId,
+        bounds: Rect,
+        background: Color4f,
+        error: Option<&str>,
+        full_redraw: bool,
+        present: impl FnOnce(Rect, &mut dyn FnMut(&Canvas)),
+    ) {
+        self.bounds = bounds;
+        let root = &mut self.root;
+        ui::track_window(window_id, || {
+            if let Some(root) = root.as_mut() {
+                root.layout(bounds);
+            }
+            let damage = match (root.as_ref(), error) {
+                _ if full_redraw => Some(bounds),
+                (Some(root), None) => root.damage(),
+                _ => Some(bounds),
+            };
+            if let Some(damage) = damage {
+                present(damage, &mut |canvas: &Canvas| {
+                    canvas.clear(background);
+                    if let Some(root) = root.as_ref() {
+                        root.paint(canvas);
+                    }
+                    if let Some(error) = error {
+                        ui::paint_error_overlay(canvas, bounds, error);
+                    }
+                });
+            }
+        });
+        if let Some(root) = &mut self.root {
+            root.mark_clean();
+        }
+    }
+}
@@ -1,20 +1,51 @@
 use self::helper::*;
+use crate::core::cursor::Cursor;
+use crate::core::diagnostics;
+use crate::core::dialog::DialogState;
+use crate::core::drag::{Drag, DragStart};
+use crate::core::frame_stats::FrameTiming;
+use crate::core::host::{HeadlessWindow, Host};
+use crate::core::monitor::Monitor;
+use crate::core::session::{self, WindowGeometry};
+use crate::core::view::{Response, View};
+use crate::core::window_change::ObservedState;
 use crate::core::*;
+use crate::ui::{
+    DragPayload, HitRegion, ResizeEdge, Store, UiDescription, UiError, Widget, WidgetKey,
+};
 
 use glutin::config::Config;
 use glutin::context::{ContextApi, ContextAttributesBuilder, PossiblyCurrentContext};
 use glutin::display::GetGlDisplay;
-use glutin::surface::{Surface as GLSurface, SurfaceAttributesBuilder, WindowSurface};
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+use glutin::surface::Rect as GLRect;
+use glutin::surface::{
+    Surface as GLSurface, SurfaceAttributesBuilder, SwapInterval, WindowSurface,
+};
 #[allow(deprecated)]
 use raw_window_handle::HasRawWindowHandle;
 use skia_safe::gpu::gl::{Format, FramebufferInfo, Interface};
 use skia_safe::gpu::surfaces::wrap_backend_render_target;
-use skia_safe::gpu::{backend_render_targets, direct_contexts, DirectContext, SurfaceOrigin};
-use skia_safe::{scalar, Canvas, ColorType, Surface};
+use skia_safe::gpu::{
+    backend_render_targets, direct_contexts, DirectContext, ResourceCacheUsage, SurfaceOrigin,
+};
+use skia_safe::{
+    scalar, surfaces, Color, Color4f, ColorType, IRect, Image, Matrix, Paint, Point, Rect,
+    RoundOut, Surface, Vector,
+};
+use std::collections::{HashSet, VecDeque};
 use std::ffi::CString;
-use winit::dpi::{LogicalSize, PhysicalSize};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use winit::dpi::{LogicalSize, PhysicalPosition, PhysicalSize};
 use winit::event_loop::ActiveEventLoop;
-use winit::window::{WindowAttributes, WindowId};
+use winit::monitor::VideoModeHandle;
+use winit::window::{
+    CursorGrabMode, Fullscreen, ResizeDirection, WindowAttributes, WindowId, WindowLevel,
+};
+
+/// Number of previous frames whose damage is remembered, to know what to repaint in older back buffers.
+const DAMAGE_HISTORY_LENGTH: usize = 4;
 
 /// A window produced by `winit`.
 ///
@@ -24,15 +55,59 @@ pub(super) type RawWindow = winit::window::Window;
 
 /// A window with a Skia canvas.
 pub struct Window {
-    raw: RawWindow,
-    gl: OpenGL,
+    host: Host,
+    gl: Option<OpenGL>,
     skia: Skia,
+    view: View,
+    ui_source: Option<UiSource>,
+    /// Whether the widget tree has been replaced since the application last watched its files.
+    ui_replaced: bool,
+    vsync: bool,
+    target_fps: Option<u32>,
+    frame_stats: FrameStats,
+    diagnostics_visible: bool,
+    damage_history: VecDeque<IRect>,
+    full_redraw: bool,
+    swap_with_damage: bool,
+    observed_state: ObservedState,
+    state_changed: bool,
+    requested_minimized: bool,
+    always_on_top: bool,
+    min_size: Option<LogicalSize<f64>>,
+    max_size: Option<LogicalSize<f64>>,
+    /// The size of the content the last time the window was neither maximized, minimized nor fullscreen.
+    normal_size: LogicalSize<f64>,
+    changes: Vec<WindowChange>,
+    resize_border: f32,
+    background: Color4f,
+    transparent: bool,
+    blur: bool,
+    click_through: bool,
+    passing_through: bool,
+    /// The estimated position of the cursor on the desktop while clicks go through the window.
+    pass_through_cursor: Option<PhysicalPosition<f64>>,
+    file_drops: Vec<FileDrop>,
+    gestures: Vec<Gesture>,
+    started_drag: Option<Drag>,
+    requested_dialogs: Vec<DialogState>,
+    drag_preview: Option<(Image, Point)>,
+    cursor: Cursor,
+    cursor_changed: bool,
+    cursor_visible: bool,
+    cursor_grab: CursorGrabMode,
+    clipboard: Clipboard,
 
     // Stuff only for rendering the example animation. Can be safely removed in an actual application.
     #[allow(missing_docs)]
     pub frame: usize,
-    #[allow(missing_docs)]
-    pub previous_frame_start: std::time::Instant,
+}
+
+/// A file describing a window's widget tree.
+struct UiSource {
+    path: PathBuf,
+    store: Store,
+    dependencies: Vec<PathBuf>,
+    error: Option<String>,
 }
 
 /// Properties to  OpenGL
@@ -42,9 +117,11 @@ struct OpenGL {
 }
 
 /// Properties required to draw with Skia.
+///
+/// Headless windows have no GPU context and draw on a CPU raster surface instead.
 struct Skia {
     surface: Surface,
-    direct_ctx: DirectContext,
+    direct_ctx: Option<DirectContext>,
     num_samples: usize,
     stencil_size: usize,
 }
@@ -62,85 +139,1165 @@ impl Window {
         Window::from_raw(initial_raw, gl_config)
     }
 
-    /// Creates a new window
-    pub(super) fn new(title: &str, event_loop: &ActiveEventLoop, gl_config: &Config) -> Self {
-        let window_attrs = Window::default_attrs();
+    /// Creates a new window with custom [`WindowAttributes`].
+    pub(super) fn with_attrs(
+        window_attrs: WindowAttributes,
+        event_loop: &ActiveEventLoop,
+        gl_config: &Config,
+    ) -> Self {
         let raw_window = glutin_winit::finalize_window(event_loop, window_attrs, gl_config)
             .expect("Could not create window with OpenGL context");
-        raw_window.set_title(title);
 
         Window::from_raw(raw_window, gl_config)
     }
 
+    /// Creates a window without a display, drawing on a CPU raster surface, with the given [`WindowAttributes`].
+    pub(super) fn headless(window_attrs: &WindowAttributes) -> Self {
+        let host = Host::Headless(HeadlessWindow::new(window_attrs));
+        let skia = Skia::raster(host.inner_size());
+        Window::from_host(host, None, skia)
+    }
+
     fn from_raw(raw: RawWindow, gl_config: &Config) -> Self {
         let gl = OpenGL::new(gl_config, &raw);
         let skia = Skia::new(&raw, gl_config);
+        Window::from_host(Host::Raw(raw), Some(gl), skia)
+    }
 
+    fn from_host(host: Host, gl: Option<OpenGL>, skia: Skia) -> Self {
         let mut window = Window {
-            raw,
+            host,
             gl,
             skia,
+            view: View::default(),
+            ui_source: None,
+            ui_replaced: false,
+            vsync: false,
+            target_fps: None,
+            frame_stats: FrameStats::default(),
+            diagnostics_visible: false,
+            damage_history: VecDeque::new(),
+            full_redraw: true,
+            swap_with_damage: true,
+            observed_state: ObservedState::default(),
+            state_changed: false,
+            requested_minimized: false,
+            always_on_top: false,
+            min_size: None,
+            max_size: None,
+            normal_size: LogicalSize::default(),
+            changes: Vec::new(),
+            resize_border: 0.0,
+            background: Color::WHITE.into(),
+            transparent: false,
+            blur: false,
+            click_through: false,
+            passing_through: false,
+            pass_through_cursor: None,
+            file_drops: Vec::new(),
+            gestures: Vec::new(),
+            started_drag: None,
+            requested_dialogs: Vec::new(),
+            drag_preview: None,
+            cursor: Cursor::default(),
+            cursor_changed: false,
+            cursor_visible: true,
+            cursor_grab: CursorGrabMode::None,
+            clipboard: Clipboard::default(),
 
             // Stuff only for rendering the example animation. Can be safely removed in an actual application.
             frame: 0,
-            previous_frame_start: std::time::Instant::now(),
         };
-        window.update_scale_factor();
+        window.set_vsync(true);
+        window.observed_state = window.current_state();
+        window.normal_size = window.host.inner_size().to_logical(window.scale_factor());
         window
     }
 
     /// Returns the window's unique ID.
     pub fn id(&self) -> WindowId {
-        self.raw.id()
+        self.host.id()
     }
 
-    /// Resets the canvas to its initial state ([Matrix](skia_safe::Matrix) and [Clip](Canvas::local_clip_bounds))
-    /// and [clears](Canvas::clear) it with the `background` color.
+    /// Returns a handle for the application's [`Clipboard`], so that the handlers receiving the window
+    /// can copy and paste.
+    pub fn clipboard(&self) -> Clipboard {
+        self.clipboard.clone()
+    }
+
+    /// Makes the window share the application's `clipboard`.
+    pub(super) fn set_clipboard(&mut self, clipboard: Clipboard) {
+        self.clipboard = clipboard;
+    }
+
+    /// Resets the canvas to its initial state ([Matrix](skia_safe::Matrix) and [Clip](skia_safe::Canvas::local_clip_bounds))
+    /// and [clears](skia_safe::Canvas::clear) it with the `background` color.
+    ///
+    /// The color's alpha is ignored unless the window is [transparent](Window::set_transparent).
     pub fn reset_canvas(&mut self, background: impl Into<skia_safe::Color4f>) {
-        self.skia.surface.canvas().restore_to_count(0);
-        self.skia.surface.canvas().reset_matrix();
-        self.update_scale_factor();
+        let background = self.clear_color(background.into());
+        self.reset_transform();
         self.skia.surface.canvas().clear(background);
     }
 
     /// Draws on the window's Skia canvas using the instructions defined in `drawing`.
-    pub fn draw(&mut self, mut drawing: impl FnMut(&Canvas)) {
+    ///
+    /// The canvas starts every frame without a clip and with a transform mapping logical pixels to
+    /// physical ones, so transformations left by the previous frame don't accumulate.
+    /// With [vsync](Window::set_vsync) enabled, presenting the frame waits for the display's next refresh.
+    /// If [diagnostics](Window::set_diagnostics_visible) are visible, they are drawn on top.
+    pub fn draw(&mut self, drawing: impl FnMut(&DrawContext)) {
+        self.make_current();
+        self.reset_transform();
+        self.present(drawing, None);
+    }
+
+    /// Draws only the `damage`d area of the window, given in logical pixels, using the instructions
+    /// defined in `drawing`, which must repaint everything inside of it, including the background.
+    ///
+    /// The canvas is reset and clipped to the area that has to be repainted. It can be larger than
+    /// the `damage` if the platform reuses older back buffers, and it covers the whole window if the
+    /// platform does not report the age of the back buffer. Where supported, only the repainted area
+    /// is sent to the compositor.
+    pub fn draw_damaged(&mut self, damage: Rect, drawing: impl FnMut(&DrawContext)) {
+        self.make_current();
+        let surface_bounds = IRect::from_wh(self.skia.surface.width(), self.skia.surface.height());
+        let scale_factor = self.scale_factor() as scalar;
+        let damage: IRect = Rect::from_ltrb(
+            damage.left * scale_factor,
+            damage.top * scale_factor,
+            damage.right * scale_factor,
+            damage.bottom * scale_factor,
+        )
+        .round_out();
+        let damage = IRect::intersect(&damage, &surface_bounds).unwrap_or_default();
+
+        // The back buffer holds the frame presented `age` frames ago,
+        // so everything damaged since then has to be repainted.
+        // Raster surfaces always keep the previous frame.
+        let age = self
+            .gl
+            .as_ref()
+            .map_or(1, |gl| gl.surface.buffer_age() as usize);
+        let repaint = if self.full_redraw
+            || self.diagnostics_visible
+            || age == 0
+            || age > self.damage_history.len() + 1
+        {
+            surface_bounds
+        } else {
+            let previous = self.damage_history.iter().take(age - 1);
+            previous.fold(damage, |repaint, damage| IRect::join(&repaint, damage))
+        };
+        if repaint.is_empty() {
+            return;
+        }
+
+        self.reset_transform();
+        let clip = Rect::from(repaint);
+        let clip = Rect::from_ltrb(
+            clip.left / scale_factor,
+            clip.top / scale_factor,
+            clip.right / scale_factor,
+            clip.bottom / scale_factor,
+        );
+        let canvas = self.skia.surface.canvas();
+        canvas.save();
+        canvas.clip_rect(clip, None, false);
+        self.present(drawing, Some(repaint));
+    }
+
+    /// Draws and presents a frame, recording its timing and its `damage`d area in physical pixels.
+    /// A `damage` of `None` means the whole window.
+    fn present(&mut self, mut drawing: impl FnMut(&DrawContext), damage: Option<IRect>) {
+        let start = Instant::now();
+        let scale_factor = self.scale_factor();
+        let size = self.host.inner_size().to_logical(scale_factor);
+        drawing(&DrawContext::new(
+            self.skia.surface.canvas(),
+            scale_factor as scalar,
+            size,
+        ));
+        let drawn = Instant::now();
+        if self.diagnostics_visible {
+            self.draw_diagnostics();
+        }
+        if let Some(direct_ctx) = &mut self.skia.direct_ctx {
+            direct_ctx.flush_and_submit();
+        }
+        let flushed = Instant::now();
+
+        let surface_bounds = IRect::from_wh(self.skia.surface.width(), self.skia.surface.height());
+        let damage = damage.unwrap_or(surface_bounds);
+        self.damage_history.push_front(damage);
+        self.damage_history.truncate(DAMAGE_HISTORY_LENGTH);
+        self.full_redraw = false;
+
+        self.host.pre_present_notify();
+        match &self.gl {
+            None => {}
+            // Only EGL can tell the compositor which part of the frame has changed.
+            #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+            Some(OpenGL {
+                surface: GLSurface::Egl(surface),
+                ctx: PossiblyCurrentContext::Egl(ctx),
+            }) if self.swap_with_damage && damage != surface_bounds => {
+                // The origin of the damage rectangles is in the bottom-left corner.
+                let rect = GLRect::new(
+                    damage.left,
+                    surface_bounds.height() - damage.bottom,
+                    damage.width(),
+                    damage.height(),
+                );
+                // Fall back to a regular swap if the platform does not support it, and keep doing so for later frames.
+                if let Err(e) = surface.swap_buffers_with_damage(ctx, &[rect]) {
+                    eprintln!("Swapping buffers with damage is not supported, swapping whole frames instead: {e}");
+                    self.swap_with_damage = false;
+                    surface.swap_buffers(ctx).unwrap();
+                }
+            }
+            Some(gl) => gl.surface.swap_buffers(&gl.ctx).unwrap(),
+        }
+        self.frame_stats.record(FrameTiming {
+            start,
+            drawn,
+            flushed,
+            presented: Instant::now(),
+        });
+    }
+
+    /// Requests the window to be redrawn completely, instead of only its damaged area.
+    pub(super) fn request_full_redraw(&mut self) {
+        self.full_redraw = true;
+        self.request_redraw();
+    }
+
+    /// Shows or hides the overlay with the window's frame statistics and GPU resource cache usage.
+    pub fn set_diagnostics_visible(&mut self, visible: bool) {
+        self.diagnostics_visible = visible;
+        self.request_full_redraw();
+    }
+
+    /// Returns `true` if the diagnostics overlay is shown.
+    pub fn diagnostics_visible(&self) -> bool {
+        self.diagnostics_visible
+    }
+
+    /// Enables or disables synchronizing the presentation of frames with the display's refresh rate.
+    ///
+    /// Vsync is enabled by default. If the platform does not support changing it, an error is printed
+    /// and the setting is left unchanged. Headless windows only remember the setting.
+    pub fn set_vsync(&mut self, enabled: bool) {
+        let gl = match &self.gl {
+            Some(gl) => gl,
+            None => {
+                self.vsync = enabled;
+                return;
+            }
+        };
+        let interval = if enabled {
+            SwapInterval::Wait(u32_to_nonzero(1))
+        } else {
+            SwapInterval::DontWait
+        };
         self.make_current();
-        drawing(self.skia.surface.canvas());
-        self.skia.direct_ctx.flush_and_submit();
-        self.gl.surface.swap_buffers(&self.gl.ctx).unwrap();
+        match gl.surface.set_swap_interval(&gl.ctx, interval) {
+            Ok(()) => self.vsync = enabled,
+            Err(e) => eprintln!("Could not change vsync: {e}"),
+        }
+    }
+
+    /// Returns `true` if presenting frames is synchronized with the display's refresh rate.
+    pub fn vsync(&self) -> bool {
+        self.vsync
+    }
+
+    /// Limits continuous redraws, such as animations, to at most `fps` frames per second,
+    /// or removes the limit if `fps` is `None`.
+    pub fn set_target_fps(&mut self, fps: Option<u32>) {
+        self.target_fps = fps.filter(|&fps| fps > 0);
+    }
+
+    /// Returns the frame rate limit of continuous redraws, if any.
+    pub fn target_fps(&self) -> Option<u32> {
+        self.target_fps
+    }
+
+    /// Returns the timing statistics of the frames presented by the window.
+    pub fn frame_stats(&self) -> &FrameStats {
+        &self.frame_stats
+    }
+
+    /// Makes the window fullscreen, either borderless or exclusive with one of the [video modes](Window::video_modes)
+    /// of a monitor, or leaves fullscreen with `None`.
+    pub fn set_fullscreen(&mut self, fullscreen: Option<Fullscreen>) {
+        self.host.set_fullscreen(fullscreen);
+        self.state_changed = true;
+    }
+
+    /// Returns the window's fullscreen mode, or `None` if it is not fullscreen.
+    pub fn fullscreen(&self) -> Option<Fullscreen> {
+        self.host.fullscreen()
+    }
+
+    /// Returns the video modes of the monitor the window is on, for [exclusive fullscreen](Fullscreen::Exclusive).
+    pub fn video_modes(&self) -> Vec<VideoModeHandle> {
+        self.host
+            .current_monitor()
+            .map(|monitor| monitor.video_modes().collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the monitors connected to the computer.
+    pub fn monitors(&self) -> Vec<Monitor> {
+        self.host
+            .available_monitors()
+            .into_iter()
+            .map(Monitor::new)
+            .collect()
+    }
+
+    /// Returns the primary monitor, if the platform has one.
+    pub fn primary_monitor(&self) -> Option<Monitor> {
+        self.host.primary_monitor().map(Monitor::new)
+    }
+
+    /// Returns the monitor the window is on, if the platform reports it.
+    pub fn current_monitor(&self) -> Option<Monitor> {
+        self.host.current_monitor().map(Monitor::new)
+    }
+
+    /// Moves the window to the center of the `monitor`. If the window is in borderless fullscreen,
+    /// it stays fullscreen on that monitor instead.
+    ///
+    /// If the monitor has a different scale factor, the window is re-laid out and redrawn for it.
+    pub fn move_to_monitor(&mut self, monitor: &Monitor) {
+        if let Some(Fullscreen::Borderless(_)) = self.fullscreen() {
+            self.set_fullscreen(Some(Fullscreen::Borderless(Some(monitor.handle().clone()))));
+            return;
+        }
+        self.host
+            .set_outer_position(monitor.center(self.host.outer_size()));
+    }
+
+    /// Maximizes the window, or restores it to its previous size.
+    pub fn set_maximized(&mut self, maximized: bool) {
+        self.host.set_maximized(maximized);
+        self.state_changed = true;
+    }
+
+    /// Returns `true` if the window is maximized.
+    pub fn is_maximized(&self) -> bool {
+        self.host.is_maximized()
+    }
+
+    /// Minimizes the window, or restores it.
+    pub fn set_minimized(&mut self, minimized: bool) {
+        self.host.set_minimized(minimized);
+        self.requested_minimized = minimized;
+        self.state_changed = true;
+    }
+
+    /// Returns `true` if the window is minimized. If the platform does not report it,
+    /// returns the state last set with [`set_minimized`](Window::set_minimized).
+    pub fn is_minimized(&self) -> bool {
+        self.host.is_minimized().unwrap_or(self.requested_minimized)
+    }
+
+    /// Keeps the window above all other windows, or puts it back among them.
+    pub fn set_always_on_top(&mut self, always_on_top: bool) {
+        let level = match always_on_top {
+            true => WindowLevel::AlwaysOnTop,
+            false => WindowLevel::Normal,
+        };
+        self.host.set_window_level(level);
+        if self.always_on_top != always_on_top {
+            self.always_on_top = always_on_top;
+            self.changes.push(WindowChange::AlwaysOnTop(always_on_top));
+        }
+    }
+
+    /// Returns `true` if the window is kept above all other windows.
+    pub fn is_always_on_top(&self) -> bool {
+        self.always_on_top
+    }
+
+    /// Shows or hides the window's title bar and borders.
+    pub fn set_decorations(&mut self, decorations: bool) {
+        let changed = self.host.is_decorated() != decorations;
+        self.host.set_decorations(decorations);
+        if changed {
+            self.changes.push(WindowChange::Decorations(decorations));
+        }
+        self.update_resize_border();
+    }
+
+    /// Returns `true` if the window has a title bar and borders.
+    pub fn has_decorations(&self) -> bool {
+        self.host.is_decorated()
+    }
+
+    /// Sets the width, in logical pixels, of the border along the window's edges that resizes the window
+    /// when dragged while its [decorations](Window::set_decorations) are hidden.
+    ///
+    /// Widgets can also be marked as [resize borders](crate::ui::HitRegion::Resize) or
+    /// [title bars](crate::ui::HitRegion::Caption) to draw custom decorations.
+    pub fn set_resize_border(&mut self, width: f32) {
+        self.resize_border = width;
+        self.update_resize_border();
+    }
+
+    /// Returns the width of the border that resizes the window while its decorations are hidden.
+    pub fn resize_border(&self) -> f32 {
+        self.resize_border
+    }
+
+    /// Enables the resize border of the window's view only while the OS does not draw the window's borders.
+    fn update_resize_border(&mut self) {
+        let width = match self.host.is_decorated() {
+            true => 0.0,
+            false => self.resize_border,
+        };
+        self.view.set_resize_border(width);
+    }
+
+    /// Sets the color the window is cleared with before its widget tree is painted.
+    ///
+    /// Colors with alpha only show what is behind the window if it is [transparent](Window::set_transparent).
+    pub fn set_background(&mut self, background: impl Into<Color4f>) {
+        self.background = background.into();
+        self.request_full_redraw();
+    }
+
+    /// Returns the color the window is cleared with before its widget tree is painted.
+    pub fn background(&self) -> Color4f {
+        self.background
+    }
+
+    /// Makes the window's background transparent, so that pixels drawn with alpha blend with what is behind it.
+    ///
+    /// Windows are created with a surface that has an alpha channel where the platform supports it,
+    /// so this only decides whether the alpha of the [background](Window::set_background) is kept
+    /// when the window is cleared. The canvas is premultiplied, like the compositor expects:
+    /// clearing it to a color with alpha and drawing translucent colors on it need no extra care.
+    pub fn set_transparent(&mut self, transparent: bool) {
+        self.transparent = transparent;
+        self.request_full_redraw();
+    }
+
+    /// Returns `true` if the window's background is transparent.
+    pub fn is_transparent(&self) -> bool {
+        self.transparent
+    }
+
+    /// Returns the `color` to clear the window with, made opaque unless the window is transparent.
+    fn clear_color(&self, color: Color4f) -> Color4f {
+        match self.transparent {
+            true => color,
+            false => color.to_opaque(),
+        }
+    }
+
+    /// Blurs what is behind the transparent parts of the window, where the platform supports it.
+    pub fn set_blur(&mut self, blur: bool) {
+        self.host.set_blur(blur);
+        self.blur = blur;
+    }
+
+    /// Returns `true` if blurring what is behind the window has been requested.
+    pub fn is_blurred(&self) -> bool {
+        self.blur
+    }
+
+    /// Makes all clicks go through the window to the windows below, e.g. for overlays.
+    ///
+    /// To let clicks through only parts of the window, mark widgets as [pass-through](HitRegion::PassThrough) instead.
+    pub fn set_click_through(&mut self, click_through: bool) {
+        self.click_through = click_through;
+        self.passing_through = false;
+        self.set_hit_test(!click_through);
+    }
+
+    /// Returns `true` if all clicks go through the window.
+    pub fn is_click_through(&self) -> bool {
+        self.click_through
+    }
+
+    /// Makes clicks go through the window while the cursor is over a [pass-through](HitRegion::PassThrough) widget.
+    ///
+    /// The window stops receiving cursor events in the meantime, so [`pass_through_motion`](Window::pass_through_motion)
+    /// has to be called when the mouse moves to check the region under the cursor again.
+    fn update_pass_through(&mut self, position: Point) {
+        let pass_through = self.view.region_at(position) == HitRegion::PassThrough;
+        if !self.click_through && pass_through != self.passing_through {
+            self.passing_through = pass_through;
+            self.pass_through_cursor = self.window_to_desktop(position).filter(|_| pass_through);
+            self.set_hit_test(!pass_through);
+        }
+    }
+
+    /// Returns `true` if clicks go through the window because the cursor is over a pass-through widget.
+    pub(super) fn is_passing_through(&self) -> bool {
+        self.passing_through
+    }
+
+    /// Follows the raw motion of the mouse by `delta` while clicks go through the window, and makes the window
+    /// receive cursor events again once the cursor is no longer over a pass-through widget.
+    ///
+    /// The position of the cursor on the desktop is estimated from the motion, which the OS may accelerate
+    /// before moving the cursor. If the platform doesn't report where the window is, the window receives
+    /// cursor events again right away, until the next cursor event shows whether the cursor is still over one.
+    pub(super) fn pass_through_motion(&mut self, delta: (f64, f64)) {
+        if !self.passing_through {
+            return;
+        }
+        let cursor = self
+            .pass_through_cursor
+            .map(|cursor| PhysicalPosition::new(cursor.x + delta.0, cursor.y + delta.1));
+        let passing_through = cursor
+            .and_then(|cursor| self.desktop_to_window(cursor))
+            .is_some_and(|position| self.view.region_at(position) == HitRegion::PassThrough);
+        self.pass_through_cursor = cursor.filter(|_| passing_through);
+        if !passing_through {
+            self.passing_through = false;
+            self.set_hit_test(true);
+        }
+    }
+
+    /// Sets whether the window receives cursor events.
+    fn set_hit_test(&self, hit_test: bool) {
+        if let Err(e) = self.host.set_cursor_hittest(hit_test) {
+            eprintln!("Could not change whether clicks go through the window: {e}");
+        }
+    }
+
+    /// Sets the cursor shown while the mouse is over the window.
+    ///
+    /// [Custom cursors](crate::core::CustomCursor) are rasterized for the window's scale factor
+    /// once the event loop has handled the pending events.
+    pub fn set_cursor(&mut self, cursor: impl Into<Cursor>) {
+        self.cursor = cursor.into();
+        match &self.cursor {
+            Cursor::Icon(icon) => {
+                self.host.set_cursor(*icon);
+                self.cursor_changed = false;
+            }
+            Cursor::Custom(_) => self.cursor_changed = true,
+        }
+    }
+
+    /// Returns the cursor shown while the mouse is over the window.
+    pub fn cursor(&self) -> &Cursor {
+        &self.cursor
+    }
+
+    /// Applies the custom cursor set since the last call, rasterized for the window's scale factor.
+    pub(super) fn apply_cursor(&mut self, event_loop: &ActiveEventLoop) {
+        if !std::mem::take(&mut self.cursor_changed) {
+            return;
+        }
+        if let Cursor::Custom(cursor) = &self.cursor {
+            if let Some(source) = cursor.rasterize(self.scale_factor()) {
+                self.host
+                    .set_cursor(event_loop.create_custom_cursor(source));
+            }
+        }
+    }
+
+    /// Shows or hides the cursor while it is over the window.
+    pub fn set_cursor_visible(&mut self, visible: bool) {
+        self.host.set_cursor_visible(visible);
+        self.cursor_visible = visible;
+    }
+
+    /// Returns `true` if the cursor is shown while it is over the window.
+    pub fn is_cursor_visible(&self) -> bool {
+        self.cursor_visible
+    }
+
+    /// Confines the cursor to the window, locks it in place, or releases it with [`CursorGrabMode::None`].
+    ///
+    /// While the cursor is locked, the window receives the raw motion of the mouse as [`Input::MouseMotion`],
+    /// e.g. for dragging a value further than the edge of the screen. Platforms that cannot lock the cursor
+    /// confine it instead, and still report the raw motion. Hiding the cursor meanwhile is up to the caller.
+    pub fn set_cursor_grab(&mut self, mode: CursorGrabMode) {
+        let result = self.host.set_cursor_grab(mode).or_else(|e| match mode {
+            CursorGrabMode::Locked => self.host.set_cursor_grab(CursorGrabMode::Confined),
+            _ => Err(e),
+        });
+        match result {
+            Ok(()) => self.cursor_grab = mode,
+            Err(e) => eprintln!("Could not grab the cursor: {e}"),
+        }
+    }
+
+    /// Returns how the cursor is grabbed by the window.
+    pub fn cursor_grab(&self) -> CursorGrabMode {
+        self.cursor_grab
+    }
+
+    /// Returns the fingers touching the window.
+    pub fn touches(&self) -> Vec<TouchPoint> {
+        self.view.touches()
+    }
+
+    /// Sets whether the mouse emulates touches, to test gestures without a touch screen.
+    ///
+    /// Dragging with the left button moves a finger, and dragging with the right button moves
+    /// two fingers on either side of where it was pressed, to pinch and rotate. Emulated touches
+    /// replace the mouse's clicks, except on captions and resize borders.
+    pub fn set_touch_emulation(&mut self, emulation: bool) {
+        self.view.set_touch_emulation(emulation);
+    }
+
+    /// Returns `true` if the mouse [emulates touches](Window::set_touch_emulation).
+    pub fn touch_emulation(&self) -> bool {
+        self.view.touch_emulation()
+    }
+
+    /// Returns `true` if the window should receive the raw motion of the mouse.
+    pub(super) fn wants_mouse_motion(&self) -> bool {
+        self.cursor_grab == CursorGrabMode::Locked && self.host.has_focus()
+    }
+
+    /// Allows or prevents the user from resizing the window.
+    pub fn set_resizable(&mut self, resizable: bool) {
+        let changed = self.host.is_resizable() != resizable;
+        self.host.set_resizable(resizable);
+        if changed {
+            self.changes.push(WindowChange::Resizable(resizable));
+        }
+    }
+
+    /// Returns `true` if the user can resize the window.
+    pub fn is_resizable(&self) -> bool {
+        self.host.is_resizable()
+    }
+
+    /// Sets the smallest size of the window's content, or removes the limit with `None`.
+    pub fn set_min_size(&mut self, size: Option<LogicalSize<f64>>) {
+        self.host.set_min_inner_size(size);
+        if self.min_size != size {
+            self.min_size = size;
+            self.changes.push(WindowChange::MinSize(size));
+        }
+    }
+
+    /// Returns the smallest size of the window's content, if it is limited.
+    pub fn min_size(&self) -> Option<LogicalSize<f64>> {
+        self.min_size
+    }
+
+    /// Sets the largest size of the window's content, or removes the limit with `None`.
+    pub fn set_max_size(&mut self, size: Option<LogicalSize<f64>>) {
+        self.host.set_max_inner_size(size);
+        if self.max_size != size {
+            self.max_size = size;
+            self.changes.push(WindowChange::MaxSize(size));
+        }
+    }
+
+    /// Returns the largest size of the window's content, if it is limited.
+    pub fn max_size(&self) -> Option<LogicalSize<f64>> {
+        self.max_size
+    }
+
+    /// Changes the window's title.
+    pub fn set_title(&mut self, title: &str) {
+        if self.host.title() != title {
+            self.changes.push(WindowChange::Title(title.to_string()));
+        }
+        self.host.set_title(title);
+    }
+
+    /// Returns the window's title.
+    pub fn title(&self) -> String {
+        self.host.title()
+    }
+
+    /// Returns the changes of the window's state since the last call.
+    ///
+    /// Fullscreen, maximized and minimized states are applied by the OS asynchronously and can also
+    /// be changed by the user, so they are compared to the last seen state after they may have changed.
+    pub(super) fn take_changes(&mut self) -> Vec<WindowChange> {
+        if std::mem::take(&mut self.state_changed) {
+            let current = self.current_state();
+            let changes = self.observed_state.changes_to(&current);
+            self.changes.extend(changes);
+            self.observed_state = current;
+        }
+        std::mem::take(&mut self.changes)
+    }
+
+    /// Returns the files dropped on the window's drop targets since the last call.
+    pub(super) fn take_file_drops(&mut self) -> Vec<FileDrop> {
+        std::mem::take(&mut self.file_drops)
+    }
+
+    /// Returns the gestures recognized by the window's widgets since the last call.
+    pub(super) fn take_gestures(&mut self) -> Vec<Gesture> {
+        std::mem::take(&mut self.gestures)
+    }
+
+    /// Returns the long press recognized if a finger has been held without moving until `now`.
+    pub(super) fn poll_gestures(&mut self, now: Instant) -> Option<Gesture> {
+        self.view.poll_gestures(now)
+    }
+
+    /// Returns when a finger held without moving will be long pressing, if one is.
+    pub(super) fn next_gesture_deadline(&self) -> Option<Instant> {
+        self.view.next_gesture_deadline()
+    }
+
+    /// Returns the drag started in the window's widget tree since the last call, if any.
+    pub(super) fn take_started_drag(&mut self) -> Option<Drag> {
+        self.started_drag.take()
+    }
+
+    /// Opens a modal `dialog` on top of the window once the current event has been handled.
+    ///
+    /// While the dialog is open, all input to the window is blocked.
+    /// The returned handle resolves to the user's choice once the dialog is closed.
+    pub fn open_dialog(&mut self, dialog: Dialog) -> DialogHandle {
+        let (state, handle) = DialogState::new(dialog, self.id());
+        self.requested_dialogs.push(state);
+        handle
+    }
+
+    /// Returns the dialogs [opened](Window::open_dialog) since the last call, to be opened by the application.
+    pub(super) fn take_requested_dialogs(&mut self) -> Vec<DialogState> {
+        std::mem::take(&mut self.requested_dialogs)
+    }
+
+    /// Starts dragging the payload of a widget, with an image of the widget as its preview.
+    fn start_drag(&self, start: DragStart, now: Instant) -> Option<Drag> {
+        let payload = start.widget.drag_payload()?.clone();
+        Some(Drag {
+            payload,
+            source: self.id(),
+            preview: self.render_drag_preview(&start.widget, start.bounds),
+            grab: start.grab,
+            hovered: None,
+            auto_scroll: Default::default(),
+            next_auto_scroll: now,
+        })
+    }
+
+    /// Paints a `widget` on its own, at the window's scale factor, scaled to its `bounds` in the window.
+    fn render_drag_preview(&self, widget: &Widget, bounds: Rect) -> Option<Image> {
+        let scale_factor = self.scale_factor() as scalar;
+        let size = (
+            (bounds.width() * scale_factor).ceil() as i32,
+            (bounds.height() * scale_factor).ceil() as i32,
+        );
+        let mut surface = surfaces::raster_n32_premul(size)?;
+        let canvas = surface.canvas();
+        canvas.scale((scale_factor, scale_factor));
+        canvas.translate((-bounds.left, -bounds.top));
+        // Widgets inside viewports are laid out in content coordinates, zoomed to their bounds in the window.
+        canvas.concat(&Matrix::rect_to_rect(widget.bounds(), bounds, None)?);
+        widget.paint(canvas);
+        Some(surface.image_snapshot())
+    }
+
+    /// Highlights the drop target at `position` if it accepts the dragged `payload`, and returns
+    /// its action and ID. A `position` of `None` removes the highlight.
+    pub(super) fn drag_over(
+        &mut self,
+        position: Option<Point>,
+        payload: &DragPayload,
+    ) -> Option<(String, Option<String>)> {
+        let target = self.view.drag_over(position, payload);
+        self.request_redraw();
+        target
+    }
+
+    /// Scrolls the viewport at `position`, in logical pixels, by `delta` while a payload is dragged near
+    /// the window's edges.
+    pub(super) fn auto_scroll(&mut self, position: Point, delta: Vector) {
+        if self.view.scroll_viewport_at(position, delta) {
+            self.request_redraw();
+        }
+    }
+
+    /// Shows the `preview` of a dragged widget with its top-left corner at the given position,
+    /// in logical pixels, or hides it with `None`.
+    pub(super) fn set_drag_preview(&mut self, preview: Option<(Image, Point)>) {
+        if preview.is_some() || self.drag_preview.is_some() {
+            self.drag_preview = preview;
+            self.request_full_redraw();
+        }
+    }
+
+    /// Returns the position in the window, in logical pixels, of a `position` on the desktop,
+    /// if it is in the window. Returns `None` if the platform doesn't report where the window is.
+    pub(super) fn desktop_to_window(&self, position: PhysicalPosition<f64>) -> Option<Point> {
+        let origin = self.host.inner_position()?;
+        let size = self.host.inner_size();
+        let (x, y) = (position.x - origin.x as f64, position.y - origin.y as f64);
+        if x < 0.0 || y < 0.0 || x >= size.width as f64 || y >= size.height as f64 {
+            return None;
+        }
+        let scale_factor = self.scale_factor();
+        Some(Point::new(
+            (x / scale_factor) as scalar,
+            (y / scale_factor) as scalar,
+        ))
+    }
+
+    /// Returns the position on the desktop of a `position` in the window, in logical pixels.
+    /// Returns `None` if the platform doesn't report where the window is.
+    pub(super) fn window_to_desktop(&self, position: Point) -> Option<PhysicalPosition<f64>> {
+        let origin = self.host.inner_position()?;
+        let scale_factor = self.scale_factor();
+        Some(PhysicalPosition::new(
+            origin.x as f64 + position.x as f64 * scale_factor,
+            origin.y as f64 + position.y as f64 * scale_factor,
+        ))
+    }
+
+    /// Returns the size of the window's content in logical pixels.
+    pub(super) fn logical_size(&self) -> LogicalSize<f32> {
+        self.host.inner_size().to_logical(self.scale_factor())
+    }
+
+    /// Returns the window's state that can also be changed by the user.
+    fn current_state(&self) -> ObservedState {
+        ObservedState {
+            fullscreen: self.host.fullscreen(),
+            maximized: self.host.is_maximized(),
+            minimized: self.is_minimized(),
+        }
+    }
+
+    /// Returns the earliest time the next continuous redraw should start to respect the
+    /// [target frame rate](Window::set_target_fps), or `None` if it can start right away.
+    pub(super) fn next_frame_time(&self) -> Option<Instant> {
+        let frame_duration = Duration::from_secs_f64(1.0 / self.target_fps? as f64);
+        Some(self.frame_stats.last_present()? + frame_duration)
+    }
+
+    /// Returns the root of the window's widget tree, if it has one.
+    pub fn ui(&self) -> Option<&Widget> {
+        self.view.root()
+    }
+
+    /// Returns a mutable reference to the root of the window's widget tree, if it has one.
+    pub fn ui_mut(&mut self) -> Option<&mut Widget> {
+        self.view.root_mut()
+    }
+
+    /// Replaces the window's widget tree with the one starting at `root`.
+    ///
+    /// The tree is laid out to fill the whole window each time it is drawn.
+    pub fn set_ui(&mut self, root: Option<Widget>) {
+        self.view.set_root(root);
+        self.ui_source = None;
+        self.ui_replaced = true;
+        self.request_full_redraw();
+    }
+
+    /// Replaces the window's widget tree with one created from the [`UiDescription`] at `path`,
+    /// binding its texts to the signals in the `store`.
+    ///
+    /// If the description cannot be loaded, the previous widget tree is kept,
+    /// and the error is shown on top of it until the description is [reloaded](Window::reload_ui).
+    /// In debug builds, the application reloads it whenever the description or any file it depends on changes.
+    pub fn load_ui(&mut self, path: impl Into<PathBuf>, store: &Store) -> Result<(), UiError> {
+        let path = path.into();
+        self.ui_source = Some(UiSource {
+            dependencies: vec![path.clone()],
+            path,
+            store: store.clone(),
+            error: None,
+        });
+        self.ui_replaced = true;
+        self.reload_ui()
+    }
+
+    /// Recreates the window's widget tree from the file it was [loaded](Window::load_ui) from.
+    pub fn reload_ui(&mut self) -> Result<(), UiError> {
+        let source = match &mut self.ui_source {
+            Some(source) => source,
+            None => return Ok(()),
+        };
+        let result = UiDescription::load(&source.path).and_then(|description| {
+            source.dependencies = description.dependencies();
+            description.instantiate(&source.store)
+        });
+        let error = match result {
+            Ok(root) => {
+                self.view.set_root(Some(root));
+                None
+            }
+            Err(e) => Some(e),
+        };
+        source.error = error.as_ref().map(ToString::to_string);
+        self.request_full_redraw();
+        error.map_or(Ok(()), Err)
+    }
+
+    /// Returns the paths of all files the window's widget tree was [loaded](Window::load_ui) from.
+    pub fn ui_dependencies(&self) -> &[PathBuf] {
+        match &self.ui_source {
+            Some(source) => &source.dependencies,
+            None => &[],
+        }
+    }
+
+    /// Returns `true` if the widget tree has been set or loaded since the last call.
+    pub(super) fn take_ui_replaced(&mut self) -> bool {
+        std::mem::take(&mut self.ui_replaced)
+    }
+
+    /// Returns `true` if the window has a widget tree or failed to load one.
+    pub(super) fn has_ui(&self) -> bool {
+        self.view.root().is_some() || self.ui_source.is_some()
+    }
+
+    /// Marks the widgets with the given `keys` as dirty and requests the window to be redrawn.
+    pub(super) fn invalidate(&mut self, keys: &HashSet<WidgetKey>) {
+        self.view.invalidate(keys);
+        self.request_redraw();
+    }
+
+    /// Lays out and draws the window's widget tree, along with the error
+    /// that occurred while reloading it, if any.
+    ///
+    /// All the [`Signal`](crate::ui::Signal)s read while drawing subscribe the window to their changes.
+    pub(super) fn draw_ui(&mut self) {
+        let background = self.clear_color(self.background);
+        let size = self.logical_size();
+        let bounds = Rect::from_wh(size.width, size.height);
+        let error = self
+            .ui_source
+            .as_ref()
+            .and_then(|source| source.error.clone());
+        let drag_preview = self.drag_preview.clone();
+        let mut view = std::mem::take(&mut self.view);
+        view.render(
+            self.id(),
+            bounds,
+            background,
+            error.as_deref(),
+            self.full_redraw,
+            |damage, paint| {
+                self.draw_damaged(damage, |context| {
+                    paint(context.canvas());
+                    if let Some((image, position)) = &drag_preview {
+                        draw_drag_preview(context, image, *position);
+                    }
+                })
+            },
+        );
+        self.view = view;
+    }
+
+    /// Handles an `input` to the window, and returns the action it triggered in the widget tree, if any.
+    pub(super) fn handle_input(&mut self, input: &Input, now: Instant) -> Option<String> {
+        match input {
+            Input::CursorMoved(position) => {
+                self.update_pass_through(Point::new(position.x, position.y))
+            }
+            Input::Resized(size) => {
+                self.host.resized(*size);
+                self.resize(*size);
+                if self.is_normal() && size.width > 0 && size.height > 0 {
+                    self.normal_size = size.to_logical(self.scale_factor());
+                }
+                // Resizes follow fullscreen, maximize and minimize, including the ones done by the user.
+                self.state_changed = true;
+            }
+            Input::ScaleFactorChanged(scale_factor) => {
+                self.host.scale_factor_changed(*scale_factor);
+                // The layout, text and images are redrawn for the new density from scratch.
+                self.request_full_redraw();
+                self.changes.push(WindowChange::ScaleFactor(*scale_factor));
+                self.cursor_changed = matches!(self.cursor, Cursor::Custom(_));
+            }
+            _ => {}
+        }
+        let response = self.view.handle_input(input, now);
+        // Inputs can highlight drop targets, or pan and zoom viewports, without changing any signal.
+        if self.view.root().is_some_and(|root| root.damage().is_some()) {
+            self.request_redraw();
+        }
+        match response? {
+            Response::Action(action) => return Some(action),
+            Response::DragMove => {
+                if let Err(e) = self.host.drag_window() {
+                    eprintln!("Could not move window: {e}");
+                }
+            }
+            Response::DragResize(edge) => {
+                if let Err(e) = self.host.drag_resize_window(resize_direction(edge)) {
+                    eprintln!("Could not resize window: {e}");
+                }
+            }
+            Response::ToggleMaximized => {
+                let maximized = self.is_maximized();
+                self.set_maximized(!maximized);
+            }
+            Response::FilesDropped(drop) => self.file_drops.push(drop),
+            Response::DragStarted(start) => self.started_drag = self.start_drag(*start, now),
+            Response::Gestures(gestures) => self.gestures.extend(gestures),
+            Response::CopyText(text) => self.clipboard.set_text(text),
+            Response::PasteText => {
+                if let Some(text) = self.clipboard.text() {
+                    self.view.paste(&text);
+                    self.request_redraw();
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns `true` if a [text input](crate::ui::WidgetKind::TextInput) of the window has the keyboard focus,
+    /// so that keys edit its text instead of triggering shortcuts.
+    pub fn is_editing_text(&self) -> bool {
+        self.view.is_editing_text()
     }
 
     /// Requests the window to be redrawn.
     pub(super) fn request_redraw(&self) {
-        self.raw.request_redraw();
+        self.host.request_redraw();
+    }
+
+    /// Returns `true` if a headless window has been requested to be redrawn since the last call.
+    ///
+    /// Windows on the display are redrawn when the OS sends [`WindowEvent::RedrawRequested`](winit::event::WindowEvent::RedrawRequested).
+    pub(super) fn take_redraw_request(&self) -> bool {
+        self.host
+            .headless()
+            .is_some_and(HeadlessWindow::take_redraw_request)
+    }
+
+    /// Returns the size a headless window has been requested to be resized to since the last call, if any.
+    ///
+    /// Windows on the display are resized by the OS, which then sends [`Input::Resized`].
+    pub(super) fn take_requested_size(&self) -> Option<PhysicalSize<u32>> {
+        self.host.headless()?.take_requested_size()
+    }
+
+    /// Returns an image of what was last drawn on the window.
+    pub(super) fn snapshot(&mut self) -> Image {
+        self.skia.surface.image_snapshot()
+    }
+
+    /// Brings the window to the front and sets input focus.
+    pub(super) fn focus(&self) {
+        self.host.focus_window();
+    }
+
+    /// Returns the scale factor used to map logical pixels to physical pixels.
+    pub fn scale_factor(&self) -> f64 {
+        self.host.scale_factor()
+    }
+
+    /// Returns the position of the window's top-left corner, including decorations,
+    /// if it is supported by the platform.
+    pub(super) fn outer_position(&self) -> Option<PhysicalPosition<i32>> {
+        self.host.outer_position()
+    }
+
+    /// Returns the size of the window in logical pixels, including decorations.
+    pub(super) fn outer_size(&self) -> LogicalSize<f32> {
+        self.host.outer_size().to_logical(self.scale_factor())
+    }
+
+    /// Returns `true` if the window is neither maximized, minimized nor fullscreen.
+    fn is_normal(&self) -> bool {
+        !self.is_maximized() && !self.is_minimized() && self.fullscreen().is_none()
+    }
+
+    /// Returns the window's current position and state, and the size it had the last time it was
+    /// neither maximized, minimized nor fullscreen, to be saved in a session.
+    pub(super) fn geometry(&self) -> WindowGeometry {
+        WindowGeometry {
+            position: self.outer_position(),
+            size: self.normal_size,
+            maximized: self.host.is_maximized(),
+            fullscreen: self.host.fullscreen().is_some(),
+        }
+    }
+
+    /// Moves, resizes, maximizes or makes the window fullscreen as saved in a session.
+    ///
+    /// The position is clamped so that the window is still on one of the available monitors.
+    pub(super) fn restore_geometry(&mut self, geometry: &WindowGeometry) {
+        self.normal_size = geometry.size;
+        self.host.request_inner_size(geometry.size);
+        if let Some(position) = geometry.position {
+            let size = geometry.size.to_physical(self.scale_factor());
+            let monitors = self.host.available_monitors().into_iter();
+            let bounds = monitors.map(|monitor| (monitor.position(), monitor.size()));
+            if let Some(position) = session::clamp_to_monitors(position, size, bounds) {
+                self.host.set_outer_position(position);
+            }
+        }
+        self.host.set_maximized(geometry.maximized);
+        if geometry.fullscreen {
+            self.host.set_fullscreen(Some(Fullscreen::Borderless(None)));
+        }
+    }
+
+    /// Asks the OS to resize the window's content to `size`, which it may refuse or adjust.
+    /// The window receives an [`Input::Resized`] once it has been resized.
+    pub(super) fn request_inner_size(&self, size: PhysicalSize<u32>) {
+        self.host.request_inner_size(size);
     }
 
     /// Resizes the window.
     pub(super) fn resize(&mut self, new_size: PhysicalSize<u32>) {
         let PhysicalSize { width, height } = new_size;
-        self.gl
-            .surface
-            .resize(&self.gl.ctx, u32_to_nonzero(width), u32_to_nonzero(height));
+        if let Some(gl) = &self.gl {
+            gl.surface
+                .resize(&gl.ctx, u32_to_nonzero(width), u32_to_nonzero(height));
+        }
         self.skia.resize_surface(new_size);
+        self.request_full_redraw();
+    }
+
+    /// Draws the diagnostics overlay, ignoring the transformations and clips left by the drawing.
+    fn draw_diagnostics(&mut self) {
+        self.reset_transform();
+        let (usage, limit) = match &self.skia.direct_ctx {
+            Some(direct_ctx) => (
+                direct_ctx.resource_cache_usage(),
+                direct_ctx.resource_cache_limit(),
+            ),
+            // Raster surfaces don't cache anything on the GPU.
+            None => (
+                ResourceCacheUsage {
+                    resource_count: 0,
+                    resource_bytes: 0,
+                },
+                0,
+            ),
+        };
+        diagnostics::draw_overlay(self.skia.surface.canvas(), &self.frame_stats, usage, limit);
     }
 
-    /// Updates the scale factor of the window's canvas.
-    fn update_scale_factor(&mut self) {
-        let scale_factor = self.raw.scale_factor() as scalar;
-        self.skia
-            .surface
-            .canvas()
-            .scale((scale_factor, scale_factor));
+    /// Removes the canvas' clip and sets its transform to map logical pixels to physical ones.
+    ///
+    /// This is the only place the scale factor is applied to the window's canvas, and it replaces
+    /// the previous transform instead of adding to it.
+    fn reset_transform(&mut self) {
+        let scale_factor = self.scale_factor() as scalar;
+        let canvas = self.skia.surface.canvas();
+        canvas.restore_to_count(0);
+        canvas.reset_matrix();
+        canvas.scale((scale_factor, scale_factor));
     }
 
     /// Makes the window's OpenGL context current. Should be called before
     /// drawing on the window's canvas.
     fn make_current(&self) {
-        self.gl
-            .ctx
-            .make_current(&self.gl.surface)
-            .expect("Could not make OpenGL context current");
+        if let Some(gl) = &self.gl {
+            gl.ctx
+                .make_current(&gl.surface)
+                .expect("Could not make OpenGL context current");
+        }
     }
 
     /// Default attributes for window creation.
@@ -148,6 +1305,35 @@ impl Window {
         WindowAttributes::default()
             .with_title("Rust Skia Template")
             .with_inner_size(LogicalSize::new(500, 500))
+            .with_transparent(true)
+    }
+}
+
+/// Draws the `image` of a dragged widget, translucent, with its top-left corner at `position`.
+fn draw_drag_preview(context: &DrawContext, image: &Image, position: Point) {
+    let scale_factor = context.scale_factor();
+    let dst = Rect::from_xywh(
+        position.x,
+        position.y,
+        image.width() as scalar / scale_factor,
+        image.height() as scalar / scale_factor,
+    );
+    let mut paint = Paint::default();
+    paint.set_alpha_f(0.7);
+    context.canvas().draw_image_rect(image, None, dst, &paint);
+}
+
+/// Converts a widget's resize edge to the direction of the window's resize.
+fn resize_direction(edge: ResizeEdge) -> ResizeDirection {
+    match edge {
+        ResizeEdge::Top => ResizeDirection::North,
+        ResizeEdge::Bottom => ResizeDirection::South,
+        ResizeEdge::Left => ResizeDirection::West,
+        ResizeEdge::Right => ResizeDirection::East,
+        ResizeEdge::TopLeft => ResizeDirection::NorthWest,
+        ResizeEdge::TopRight => ResizeDirection::NorthEast,
+        ResizeEdge::BottomLeft => ResizeDirection::SouthWest,
+        ResizeEdge::BottomRight => ResizeDirection::SouthEast,
     }
 }
 
@@ -229,12 +1415,27 @@ impl Skia {
 
         Skia {
             surface,
-            direct_ctx,
+            direct_ctx: Some(direct_ctx),
             num_samples,
             stencil_size,
         }
     }
 
+    /// Creates a CPU raster surface of the given `size`, for a headless window.
+    fn raster(size: PhysicalSize<u32>) -> Self {
+        Skia {
+            surface: Self::create_raster_surface(size),
+            direct_ctx: None,
+            num_samples: 0,
+            stencil_size: 0,
+        }
+    }
+
+    fn create_raster_surface(size: PhysicalSize<u32>) -> Surface {
+        let size = (size.width.max(1) as i32, size.height.max(1) as i32);
+        surfaces::raster_n32_premul(size).expect("Could not create raster surface")
+    }
+
     fn create_surface(
         direct_ctx: &mut DirectContext,
         size: PhysicalSize<u32>,
@@ -269,12 +1470,12 @@ impl Skia {
     }
 
     fn resize_surface(&mut self, size: PhysicalSize<u32>) {
-        self.surface = Self::create_surface(
-            &mut self.direct_ctx,
-            size,
-            self.num_samples,
-            self.stencil_size,
-        );
+        self.surface = match &mut self.direct_ctx {
+            Some(direct_ctx) => {
+                Self::create_surface(direct_ctx, size, self.num_samples, self.stencil_size)
+            }
+            None => Self::create_raster_surface(size),
+        };
     }
 }
 
@@ -287,3 +1488,31 @@ mod helper {
         NonZeroU32::new(value).unwrap_or(NonZeroU32::MIN)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::core::Harness;
+    use winit::dpi::{LogicalSize, PhysicalSize};
+
+    #[test]
+    fn maximized_windows_are_saved_and_restored_with_their_normal_size() {
+        let mut harness = Harness::new(PhysicalSize::new(800, 600));
+        harness.window_mut().unwrap().set_maximized(true);
+        harness.resize(PhysicalSize::new(1920, 1080));
+
+        let geometry = harness.window().unwrap().geometry();
+        assert!(geometry.maximized);
+        assert_eq!(geometry.size, LogicalSize::new(800.0, 600.0));
+
+        let mut restored = Harness::new(PhysicalSize::new(640, 480));
+        restored.window_mut().unwrap().restore_geometry(&geometry);
+        restored.update();
+        let window = restored.window().unwrap();
+        assert!(window.is_maximized());
+        assert_eq!(window.geometry(), geometry);
+
+        restored.window_mut().unwrap().set_maximized(false);
+        restored.resize(PhysicalSize::new(800, 600));
+        assert!(!restored.window().unwrap().geometry().maximized);
+    }
+}
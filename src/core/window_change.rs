@@ -0,0 +1,56 @@
+use winit::dpi::LogicalSize;
+use winit::window::Fullscreen;
+
+/// A change of a window's state, made by the application or by the user through the OS.
+///
+/// Changes are passed to the application's [window change handler](crate::core::Application::with_window_change_handler)
+/// once the event loop has handled the pending events.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WindowChange {
+    /// The window has entered or left fullscreen.
+    Fullscreen(Option<Fullscreen>),
+    /// The window has been maximized or restored.
+    Maximized(bool),
+    /// The window has been minimized or restored.
+    Minimized(bool),
+    /// The window has been put above or back among the other windows.
+    AlwaysOnTop(bool),
+    /// The window's decorations have been shown or hidden.
+    Decorations(bool),
+    /// The window has been made resizable or not.
+    Resizable(bool),
+    /// The window's minimum content size has changed.
+    MinSize(Option<LogicalSize<f64>>),
+    /// The window's maximum content size has changed.
+    MaxSize(Option<LogicalSize<f64>>),
+    /// The window's title has changed.
+    Title(String),
+    /// The window has moved to a monitor with a different scale factor.
+    ScaleFactor(f64),
+}
+
+/// The window state that can also be changed by the user through the OS,
+/// as last seen by the application.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(super) struct ObservedState {
+    pub(super) fullscreen: Option<Fullscreen>,
+    pub(super) maximized: bool,
+    pub(super) minimized: bool,
+}
+
+impl ObservedState {
+    /// Returns the changes from this state to the `current` one.
+    pub(super) fn changes_to(&self, current: &ObservedState) -> Vec<WindowChange> {
+        let mut changes = Vec::new();
+        if self.fullscreen != current.fullscreen {
+            changes.push(WindowChange::Fullscreen(current.fullscreen.clone()));
+        }
+        if self.maximized != current.maximized {
+            changes.push(WindowChange::Maximized(current.maximized));
+        }
+        if self.minimized != current.minimized {
+            changes.push(WindowChange::Minimized(current.minimized));
+        }
+        changes
+    }
+}
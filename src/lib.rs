@@ -3,3 +3,4 @@
 
 mod renderer;
 pub mod core;
+pub mod ui;
@@ -1,11 +1,91 @@
-use rust_gui_template::core::Application;
+use rust_gui_template::core::{Application, Recording, SessionStore};
+use rust_gui_template::ui::GestureKind;
+use std::env;
+use std::process;
+use std::thread;
+use std::time::Duration;
 
 fn main() {
     run_example();
 }
 
+/// A message sent to the example application from a background thread.
+enum ExampleMessage {
+    /// The number of seconds the application has been running for.
+    Uptime(u64),
+}
+
 /// Runs the example application.
+///
+/// `--record <file>` records the session's inputs to a file, and `--replay <file>` replays a recorded session.
+/// Otherwise, the windows of the previous session are restored.
 pub fn run_example() {
-    let app = Application::new();
+    let mut app = Application::<ExampleMessage>::new()
+        .with_message_handler(|message, store| match message {
+            ExampleMessage::Uptime(seconds) => {
+                store.set("uptime", format!("Running for {seconds} s"))
+            }
+        })
+        .with_window_change_handler(|window, change, store| {
+            store.set("window_state", format!("{}: {change:?}", window.title()))
+        })
+        .with_file_drop_handler(|_, drop, store| {
+            let names: Vec<_> = drop
+                .paths
+                .iter()
+                .filter_map(|path| path.file_name())
+                .map(|name| name.to_string_lossy())
+                .collect();
+            store.set("dropped_files", format!("Dropped {}", names.join(", ")))
+        })
+        .with_drag_drop_handler(|window, drop, store| {
+            if let Some(fruit) = drop.payload.value::<String>() {
+                store.set("basket", format!("{fruit} dropped in {}", window.title()))
+            }
+        })
+        .with_gesture_handler(|_, gesture, store| {
+            let details = match gesture.kind {
+                GestureKind::Pan => format!(
+                    " by {:.0}, {:.0}",
+                    gesture.translation.x, gesture.translation.y
+                ),
+                GestureKind::Pinch => format!(" to {:.0}%", gesture.scale * 100.0),
+                GestureKind::Rotate => format!(" by {:.0}°", gesture.rotation.to_degrees()),
+                _ => String::new(),
+            };
+            store.set(
+                "gesture",
+                format!("{:?} {:?}{details}", gesture.kind, gesture.phase),
+            )
+        });
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.as_slice() {
+        // Restoring the previous session would change the windows a recording is replayed to.
+        [] => app = app.with_session_store(SessionStore::new("rust-gui-example")),
+        [flag, path] if flag == "--record" => app = app.with_recording(path),
+        [flag, path] if flag == "--replay" => match Recording::load(path) {
+            Ok(recording) => app = app.with_replay(recording),
+            Err(e) => {
+                eprintln!("{e}");
+                process::exit(1);
+            }
+        },
+        _ => {
+            eprintln!("Usage: rust-gui-example [--record <file> | --replay <file>]");
+            process::exit(1);
+        }
+    }
+
+    let proxy = app.proxy();
+    thread::spawn(move || {
+        for seconds in 1.. {
+            thread::sleep(Duration::from_secs(1));
+            if proxy.send(ExampleMessage::Uptime(seconds)).is_err() {
+                break;
+            }
+        }
+    });
+
     app.run();
-}
\ No newline at end of file
+}
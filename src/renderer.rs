@@ -5,10 +5,10 @@
 // Released under the MIT license: https://opensource.org/licenses/MIT
 #![allow(unknown_lints)]
 #![allow(clippy::unusual_byte_groupings)]
+use crate::core::DrawContext;
 use skia_safe::{
-    gradient_shader, scalar, Color, Matrix, Paint, PaintJoin, PaintStyle, Path, Point, TileMode,
+    gradient_shader, Color, Matrix, Paint, PaintJoin, PaintStyle, Path, Point, TileMode,
 };
-use std::cmp::min;
 
 const PI: f32 = std::f32::consts::PI;
 const DEGREES_IN_RADIANS: f32 = PI / 180.0;
@@ -21,22 +21,14 @@ fn point_in_circle(center: (f32, f32), radius: f32, radians: f32) -> (f32, f32)
     )
 }
 
-pub fn render_frame(
-    frame: usize,
-    fps: usize,
-    bpm: usize,
-    canvas: &skia_safe::canvas::Canvas,
-) -> usize {
+pub fn render_frame(frame: usize, fps: usize, bpm: usize, context: &DrawContext) -> usize {
+    let canvas = context.canvas();
     let step = 60.0 * bpm as f32 / 60.0 / fps as f32;
     let frame_count = (360.0 / step) as usize;
 
     let size = {
-        let dim = canvas.image_info().dimensions();
-        let size = min(dim.width, dim.height);
-
-        // We need to revert the scale factor applied to the canvas by the window.
-        let scale = canvas.local_to_device_as_3x3().scale_x();
-        (size as scalar / scale) as i32
+        let size = context.size();
+        size.width.min(size.height) as i32
     };
 
     let center = (size / 2, size / 2);
@@ -351,3 +343,23 @@ fn gradient(paint: &mut Paint, center: (f32, f32), radii: (f32, f32), colors: (C
         &matrix,
     ));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Snapshot;
+
+    const GOLDEN_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/animation.png");
+
+    // The golden image has to be written on a machine with Skia, with `UPDATE_GOLDENS=1 cargo test -- --ignored`,
+    // and committed before the test can run.
+    #[test]
+    #[ignore = "tests/golden/animation.png has not been committed yet"]
+    fn frame_matches_golden() {
+        Snapshot::new(400, 300)
+            .with_tolerance(2)
+            .assert(GOLDEN_PATH, |context| {
+                render_frame(90, 60, 60, context);
+            });
+    }
+}
@@ -0,0 +1,62 @@
+//! A module with the widget tree of a window and its declarative description.
+
+mod drag_payload;
+mod drop_target;
+mod gesture_recognizer;
+mod markup;
+mod state;
+mod text_input;
+mod viewport;
+mod widget;
+
+pub use drag_payload::DragPayload;
+pub use drop_target::DropTarget;
+pub use gesture_recognizer::{GestureKind, GestureRecognizer};
+pub use markup::{UiDescription, UiError};
+pub(crate) use state::{take_invalidated, track_window};
+pub use state::{Signal, Store, WidgetKey};
+pub use text_input::TextInput;
+pub use viewport::Viewport;
+pub use widget::{HitRegion, ImageContent, Layout, ResizeEdge, Style, Widget, WidgetKind};
+
+use skia_safe::{Canvas, Color, Font, FontMgr, FontStyle, Paint, Rect, Typeface};
+
+thread_local! {
+    /// The system's default typeface, loaded once per thread.
+    static DEFAULT_TYPEFACE: Typeface = FontMgr::new()
+        .legacy_make_typeface(None, FontStyle::default())
+        .expect("Could not load the default typeface");
+}
+
+/// Returns the system's default font with the given `size`.
+pub(crate) fn default_font(size: f32) -> Font {
+    DEFAULT_TYPEFACE.with(|typeface| Font::new(typeface.clone(), size))
+}
+
+/// Paints an `error` message in a banner at the bottom of the `bounds`.
+pub(crate) fn paint_error_overlay(canvas: &Canvas, bounds: Rect, error: &str) {
+    const FONT_SIZE: f32 = 13.0;
+    const LINE_HEIGHT: f32 = FONT_SIZE * 1.4;
+    const PADDING: f32 = 12.0;
+
+    let lines: Vec<_> = error.lines().collect();
+    let height = lines.len() as f32 * LINE_HEIGHT + PADDING * 2.0;
+    let banner = Rect::from_ltrb(
+        bounds.left,
+        bounds.bottom - height,
+        bounds.right,
+        bounds.bottom,
+    );
+
+    let mut paint = Paint::default();
+    paint.set_anti_alias(true);
+    paint.set_color(Color::from_argb(0xe6, 0xb3, 0x26, 0x1e));
+    canvas.draw_rect(banner, &paint);
+
+    let font = default_font(FONT_SIZE);
+    paint.set_color(Color::WHITE);
+    for (i, line) in lines.iter().enumerate() {
+        let baseline = banner.top + PADDING + FONT_SIZE + i as f32 * LINE_HEIGHT;
+        canvas.draw_str(line, (banner.left + PADDING, baseline), &font, &paint);
+    }
+}
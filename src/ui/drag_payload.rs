@@ -0,0 +1,44 @@
+use std::any::Any;
+use std::fmt::{self, Debug, Formatter};
+use std::rc::Rc;
+
+/// The data dragged from a [`Widget`](crate::ui::Widget) to a [`DropTarget`](crate::ui::DropTarget),
+/// within a window or between the application's windows.
+///
+/// The `kind` tells drop targets whether they accept the payload, and the value can be any type.
+/// Payloads are cheap to clone; all clones share the same value.
+#[derive(Clone)]
+pub struct DragPayload {
+    kind: String,
+    value: Rc<dyn Any>,
+}
+
+impl DragPayload {
+    /// Creates a payload of the given `kind` carrying a `value`.
+    pub fn new<T: Any>(kind: impl Into<String>, value: T) -> Self {
+        DragPayload {
+            kind: kind.into(),
+            value: Rc::new(value),
+        }
+    }
+
+    /// Returns the payload's kind.
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    /// Returns the payload's value, if it is a `T`.
+    ///
+    /// Payloads of [UI descriptions](crate::ui::UiDescription) carry a [`String`].
+    pub fn value<T: Any>(&self) -> Option<&T> {
+        self.value.downcast_ref()
+    }
+}
+
+impl Debug for DragPayload {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DragPayload")
+            .field("kind", &self.kind)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,181 @@
+use crate::ui::DragPayload;
+
+use serde::Deserialize;
+use std::path::Path;
+
+/// MIME types of common file extensions, used to filter the files a [`DropTarget`] accepts.
+const MIME_TYPES: &[(&str, &str)] = &[
+    ("bmp", "image/bmp"),
+    ("css", "text/css"),
+    ("csv", "text/csv"),
+    ("gif", "image/gif"),
+    ("htm", "text/html"),
+    ("html", "text/html"),
+    ("jpeg", "image/jpeg"),
+    ("jpg", "image/jpeg"),
+    ("json", "application/json"),
+    ("md", "text/markdown"),
+    ("mp3", "audio/mpeg"),
+    ("mp4", "video/mp4"),
+    ("ogg", "audio/ogg"),
+    ("pdf", "application/pdf"),
+    ("png", "image/png"),
+    ("ron", "text/plain"),
+    ("rs", "text/x-rust"),
+    ("svg", "image/svg+xml"),
+    ("toml", "application/toml"),
+    ("ttf", "font/ttf"),
+    ("txt", "text/plain"),
+    ("wav", "audio/wav"),
+    ("webm", "video/webm"),
+    ("webp", "image/webp"),
+    ("xml", "application/xml"),
+    ("zip", "application/zip"),
+];
+
+/// Makes a [`Widget`](crate::ui::Widget) accept files dragged from other applications,
+/// or [payloads](crate::ui::DragPayload) dragged from widgets of the application's windows.
+///
+/// The widget is highlighted while accepted files or payloads hover over it, and dropping them on it
+/// triggers its drop action. A target without extensions, MIME types or payload kinds accepts all files,
+/// and payloads are only accepted if their kind is listed.
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DropTarget {
+    /// Name of the action triggered when files or payloads are dropped on the widget.
+    pub action: String,
+    /// Accepted file extensions, without the leading dot. They are compared ignoring case.
+    pub extensions: Vec<String>,
+    /// Accepted MIME types, such as `image/png`, or `image/*` for all images.
+    /// The type of a file is guessed from its extension.
+    pub mime_types: Vec<String>,
+    /// Accepted [payload kinds](crate::ui::DragPayload::kind).
+    pub kinds: Vec<String>,
+}
+
+impl DropTarget {
+    /// Creates a drop target accepting all files and triggering the given `action`.
+    pub fn new(action: impl Into<String>) -> Self {
+        DropTarget {
+            action: action.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Accepts only files with the given extensions, or the [MIME types](DropTarget::with_mime_types).
+    pub fn with_extensions<S: Into<String>>(
+        mut self,
+        extensions: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.extensions = extensions.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Accepts only files of the given MIME types, or the [extensions](DropTarget::with_extensions).
+    pub fn with_mime_types<S: Into<String>>(
+        mut self,
+        mime_types: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.mime_types = mime_types.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Accepts payloads of the given kinds, dragged from the application's widgets.
+    pub fn with_kinds<S: Into<String>>(mut self, kinds: impl IntoIterator<Item = S>) -> Self {
+        self.kinds = kinds.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Returns `true` if the file at `path` can be dropped on the target.
+    pub fn accepts(&self, path: &Path) -> bool {
+        if self.extensions.is_empty() && self.mime_types.is_empty() && self.kinds.is_empty() {
+            return true;
+        }
+        let extension = match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) => extension,
+            None => return false,
+        };
+        let accepts_extension = self
+            .extensions
+            .iter()
+            .any(|accepted| accepted.eq_ignore_ascii_case(extension));
+        let accepts_mime_type = mime_type(extension).is_some_and(|mime_type| {
+            self.mime_types
+                .iter()
+                .any(|accepted| mime_type_matches(accepted, mime_type))
+        });
+        accepts_extension || accepts_mime_type
+    }
+
+    /// Returns `true` if the `payload` can be dropped on the target.
+    pub fn accepts_payload(&self, payload: &DragPayload) -> bool {
+        self.kinds.iter().any(|kind| kind == payload.kind())
+    }
+}
+
+/// Guesses the MIME type of a file from its `extension`.
+fn mime_type(extension: &str) -> Option<&'static str> {
+    MIME_TYPES
+        .iter()
+        .find(|(known, _)| known.eq_ignore_ascii_case(extension))
+        .map(|(_, mime_type)| *mime_type)
+}
+
+/// Returns `true` if the `mime_type` matches the `pattern`, which can end with `/*` to match any subtype.
+fn mime_type_matches(pattern: &str, mime_type: &str) -> bool {
+    match pattern.strip_suffix("/*") {
+        Some(type_) => mime_type
+            .split_once('/')
+            .is_some_and(|(main_type, _)| main_type.eq_ignore_ascii_case(type_)),
+        None => pattern.eq_ignore_ascii_case(mime_type),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn targets_without_filters_accept_all_files() {
+        let target = DropTarget::new("open");
+        assert!(target.accepts(Path::new("notes.txt")));
+        assert!(target.accepts(Path::new("Makefile")));
+    }
+
+    #[test]
+    fn extensions_are_matched_ignoring_case() {
+        let target = DropTarget::new("open").with_extensions(["png", "jpg"]);
+        assert!(target.accepts(Path::new("/photos/cat.PNG")));
+        assert!(target.accepts(Path::new("dog.jpg")));
+        assert!(!target.accepts(Path::new("dog.jpeg")));
+        assert!(!target.accepts(Path::new("png")));
+    }
+
+    #[test]
+    fn mime_types_are_guessed_from_extensions() {
+        let target = DropTarget::new("open").with_mime_types(["text/*", "application/pdf"]);
+        assert!(target.accepts(Path::new("main.rs")));
+        assert!(target.accepts(Path::new("README.md")));
+        assert!(target.accepts(Path::new("paper.pdf")));
+        assert!(!target.accepts(Path::new("song.mp3")));
+        assert!(!target.accepts(Path::new("unknown.xyz")));
+    }
+
+    #[test]
+    fn targets_for_payloads_reject_files() {
+        let target = DropTarget::new("move").with_kinds(["item"]);
+        assert!(!target.accepts(Path::new("notes.txt")));
+        assert!(target.accepts_payload(&DragPayload::new("item", 3)));
+        assert!(!target.accepts_payload(&DragPayload::new("fruit", 3)));
+    }
+
+    #[test]
+    fn mime_type_patterns_match_subtypes() {
+        assert!(mime_type_matches("image/*", "image/png"));
+        assert!(mime_type_matches("IMAGE/*", "image/svg+xml"));
+        assert!(mime_type_matches("text/plain", "text/plain"));
+        assert!(!mime_type_matches("text/plain", "text/markdown"));
+        assert!(!mime_type_matches("image/*", "video/mp4"));
+        assert!(!mime_type_matches("image/*", "image"));
+    }
+}
@@ -0,0 +1,41 @@
+use serde::Deserialize;
+
+/// The gestures a [`GestureRecognizer`] can recognize.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+pub enum GestureKind {
+    /// A single finger touching and leaving the screen without moving.
+    Tap,
+    /// Two taps in quick succession, or a double tap on a touchpad.
+    DoubleTap,
+    /// A single finger held on the screen without moving.
+    LongPress,
+    /// One or more fingers moving together, or a pan on a touchpad.
+    Pan,
+    /// Two or more fingers moving apart or together, or a pinch on a touchpad.
+    Pinch,
+    /// Two or more fingers turning around their center, or a rotation on a touchpad.
+    Rotate,
+}
+
+/// Makes a [`Widget`](crate::ui::Widget) recognize a kind of gesture started on it,
+/// and report it with an action.
+///
+/// A widget can have several recognizers, e.g. for pinching and rotating at the same time.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GestureRecognizer {
+    /// The recognized gesture.
+    pub kind: GestureKind,
+    /// Name of the action reported with the gesture.
+    pub action: String,
+}
+
+impl GestureRecognizer {
+    /// Creates a recognizer of the given `kind` of gesture, reported with the given `action`.
+    pub fn new(kind: GestureKind, action: impl Into<String>) -> Self {
+        GestureRecognizer {
+            kind,
+            action: action.into(),
+        }
+    }
+}
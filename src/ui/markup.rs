@@ -0,0 +1,374 @@
+use crate::ui::{
+    DragPayload, DropTarget, GestureRecognizer, HitRegion, ImageContent, Layout, Store, Style,
+    TextInput, Viewport, Widget, WidgetKind,
+};
+
+use ron::extensions::Extensions;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use skia_safe::{svg, Color, Data, FontMgr, Image};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::{fmt, fs, io};
+
+/// A declarative description of a widget tree, written in [RON](https://github.com/ron-rs/ron).
+///
+/// The description consists of a `root` node and optional named `styles`,
+/// which nodes can refer to with their `class`. Shared styles can also be loaded
+/// from a `theme` file, containing just the map of styles.
+/// Paths to the theme and images are relative to the description's file:
+///
+/// ```ron
+/// (
+///     theme: "theme.ron",
+///     styles: {
+///         "primary": (background: "#3a6ea5", foreground: "#ffffff"),
+///     },
+///     root: (
+///         kind: Column,
+///         layout: (padding: 16, spacing: 8),
+///         children: [
+///             (kind: Image, source: "logo.svg", layout: (width: 64, height: 64)),
+///             (kind: Label, bind: "greeting"),
+///             (kind: TextInput, bind: "name"),
+///             (kind: Button, text: "Open a window", class: "primary", action: "open_window"),
+///         ],
+///     ),
+/// )
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UiDescription {
+    #[serde(default)]
+    theme: Option<PathBuf>,
+    #[serde(default)]
+    styles: HashMap<String, StyleDescription>,
+    root: NodeDescription,
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+/// An error that occurred while loading a [`UiDescription`].
+#[derive(Debug)]
+pub enum UiError {
+    /// The file could not be read.
+    Io(PathBuf, io::Error),
+    /// The description or theme in the given file is not valid RON
+    /// or does not match the expected structure.
+    Parse(Option<PathBuf>, ron::error::SpannedError),
+    /// The image in the given file could not be decoded.
+    InvalidImage(PathBuf),
+    /// A color is not in the `#rrggbb` or `#rrggbbaa` format.
+    InvalidColor(String),
+    /// A node refers to a style that is not defined.
+    UnknownStyle(String),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NodeDescription {
+    kind: NodeKind,
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    source: Option<PathBuf>,
+    #[serde(default)]
+    bind: Option<String>,
+    #[serde(default)]
+    action: Option<String>,
+    #[serde(default)]
+    region: HitRegion,
+    #[serde(default)]
+    drop_target: Option<DropTarget>,
+    #[serde(default)]
+    drag: Option<DragDescription>,
+    #[serde(default)]
+    gestures: Vec<GestureRecognizer>,
+    #[serde(default)]
+    viewport: Viewport,
+    #[serde(default)]
+    class: Option<String>,
+    #[serde(default)]
+    layout: Layout,
+    #[serde(default)]
+    style: StyleDescription,
+    #[serde(default)]
+    children: Vec<NodeDescription>,
+}
+
+/// The payload dragged from a node, carrying its `data` as a [`String`].
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DragDescription {
+    kind: String,
+    #[serde(default)]
+    data: String,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+enum NodeKind {
+    Column,
+    Row,
+    Label,
+    Button,
+    Image,
+    Spacer,
+    Viewport,
+    TextInput,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct StyleDescription {
+    background: Option<String>,
+    foreground: Option<String>,
+    border: Option<String>,
+    corner_radius: Option<f32>,
+    font_size: Option<f32>,
+}
+
+impl UiDescription {
+    /// Parses a description from a RON `source`.
+    ///
+    /// Optional values can be written without wrapping them in `Some`.
+    /// Relative paths in the description are resolved against the current working directory.
+    pub fn parse(source: &str) -> Result<Self, UiError> {
+        parse_ron(source).map_err(|e| UiError::Parse(None, e))
+    }
+
+    /// Reads and parses a description from the file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, UiError> {
+        let path = path.as_ref();
+        let source = read_file(path)?;
+        let mut description: UiDescription =
+            parse_ron(&source).map_err(|e| UiError::Parse(Some(path.to_path_buf()), e))?;
+        description.path = Some(path.to_path_buf());
+        Ok(description)
+    }
+
+    /// Returns the paths of all files the widget tree is created from:
+    /// the description itself (if it was [loaded](UiDescription::load) from a file),
+    /// the theme and the images.
+    pub fn dependencies(&self) -> Vec<PathBuf> {
+        let mut dependencies: Vec<_> = self.path.iter().cloned().collect();
+        dependencies.extend(self.theme.iter().map(|theme| self.resolve_path(theme)));
+        self.root.collect_sources(&mut |source| {
+            dependencies.push(self.resolve_path(source));
+        });
+        dependencies
+    }
+
+    /// Creates a widget tree from the description.
+    ///
+    /// Texts of nodes with a `bind` property are bound to the signals with the same name in the `store`.
+    pub fn instantiate(&self, store: &Store) -> Result<Widget, UiError> {
+        let mut styles = match &self.theme {
+            Some(theme) => {
+                let path = self.resolve_path(theme);
+                let source = read_file(&path)?;
+                parse_ron::<HashMap<String, StyleDescription>>(&source)
+                    .map_err(|e| UiError::Parse(Some(path), e))?
+                    .iter()
+                    .map(|(name, style)| Ok((name.clone(), style.resolve()?)))
+                    .collect::<Result<HashMap<_, _>, UiError>>()?
+            }
+            None => HashMap::new(),
+        };
+        for (name, style) in &self.styles {
+            styles.insert(name.clone(), style.resolve()?);
+        }
+        self.root
+            .instantiate(&styles, store, &|path| self.resolve_path(path))
+    }
+
+    /// Resolves a `path` relative to the description's file.
+    fn resolve_path(&self, path: &Path) -> PathBuf {
+        match self.path.as_deref().and_then(Path::parent) {
+            Some(base_dir) => base_dir.join(path),
+            None => path.to_path_buf(),
+        }
+    }
+}
+
+impl NodeDescription {
+    fn instantiate(
+        &self,
+        styles: &HashMap<String, Style>,
+        store: &Store,
+        resolve_path: &dyn Fn(&Path) -> PathBuf,
+    ) -> Result<Widget, UiError> {
+        let kind = match self.kind {
+            NodeKind::Column => WidgetKind::Column,
+            NodeKind::Row => WidgetKind::Row,
+            NodeKind::Label => WidgetKind::Label(self.text.clone()),
+            NodeKind::Button => WidgetKind::Button(self.text.clone()),
+            NodeKind::Image => {
+                let source = self.source.as_deref().unwrap_or(Path::new(""));
+                WidgetKind::Image(load_image(&resolve_path(source))?)
+            }
+            NodeKind::Spacer => WidgetKind::Spacer,
+            NodeKind::Viewport => WidgetKind::Viewport(self.viewport),
+            // Bound text inputs start with the text of their signal.
+            NodeKind::TextInput => WidgetKind::TextInput(TextInput::new(match &self.bind {
+                Some(name) => store.signal(name).with_untracked(String::clone),
+                None => self.text.clone(),
+            })),
+        };
+        let class = match &self.class {
+            Some(class) => *styles
+                .get(class)
+                .ok_or_else(|| UiError::UnknownStyle(class.clone()))?,
+            None => Style::default(),
+        };
+
+        let mut widget = Widget::new(kind)
+            .with_layout(self.layout)
+            .with_hit_region(self.region)
+            .with_style(self.style.resolve()?.or(class));
+        if let Some(id) = &self.id {
+            widget = widget.with_id(id);
+        }
+        if let Some(action) = &self.action {
+            widget = widget.with_action(action);
+        }
+        if let Some(drop_target) = &self.drop_target {
+            widget = widget.with_drop_target(drop_target.clone());
+        }
+        if let Some(drag) = &self.drag {
+            widget = widget.with_drag_payload(DragPayload::new(&drag.kind, drag.data.clone()));
+        }
+        for recognizer in &self.gestures {
+            widget = widget.with_gesture(recognizer.clone());
+        }
+        if let Some(name) = &self.bind {
+            widget = widget.with_text_binding(store.signal(name));
+        }
+        for child in &self.children {
+            widget = widget.with_child(child.instantiate(styles, store, resolve_path)?);
+        }
+        Ok(widget)
+    }
+
+    /// Calls `f` with the image source of this node and all of its descendants.
+    fn collect_sources(&self, f: &mut dyn FnMut(&Path)) {
+        if let Some(source) = &self.source {
+            f(source);
+        }
+        for child in &self.children {
+            child.collect_sources(f);
+        }
+    }
+}
+
+impl StyleDescription {
+    fn resolve(&self) -> Result<Style, UiError> {
+        let color = |value: &Option<String>| value.as_deref().map(parse_color).transpose();
+        Ok(Style {
+            background: color(&self.background)?,
+            foreground: color(&self.foreground)?,
+            border: color(&self.border)?,
+            corner_radius: self.corner_radius,
+            font_size: self.font_size,
+        })
+    }
+}
+
+/// Parses a RON `source`, allowing optional values to be written without wrapping them in `Some`.
+fn parse_ron<T: DeserializeOwned>(source: &str) -> ron::error::SpannedResult<T> {
+    ron::Options::default()
+        .with_default_extension(Extensions::IMPLICIT_SOME)
+        .from_str(source)
+}
+
+fn read_file(path: &Path) -> Result<String, UiError> {
+    fs::read_to_string(path).map_err(|e| UiError::Io(path.to_path_buf(), e))
+}
+
+/// Loads an SVG document or a raster image, depending on the file's extension.
+fn load_image(path: &Path) -> Result<ImageContent, UiError> {
+    let bytes = fs::read(path).map_err(|e| UiError::Io(path.to_path_buf(), e))?;
+    let is_svg = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("svg"));
+    let content = if is_svg {
+        svg::Dom::from_bytes(&bytes, FontMgr::new())
+            .ok()
+            .map(ImageContent::Svg)
+    } else {
+        Image::from_encoded(Data::new_copy(&bytes)).map(ImageContent::Raster)
+    };
+    content.ok_or_else(|| UiError::InvalidImage(path.to_path_buf()))
+}
+
+/// Parses a color in the `#rrggbb` or `#rrggbbaa` format.
+fn parse_color(value: &str) -> Result<Color, UiError> {
+    let invalid = || UiError::InvalidColor(value.to_string());
+    let hex = value.strip_prefix('#').ok_or_else(invalid)?;
+    // `from_str_radix` accepts a leading sign, so the digits are checked first.
+    if !(hex.len() == 6 || hex.len() == 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| invalid());
+    let alpha = if hex.len() == 8 { channel(6)? } else { 0xff };
+    Ok(Color::from_argb(
+        alpha,
+        channel(0)?,
+        channel(2)?,
+        channel(4)?,
+    ))
+}
+
+impl Display for UiError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            UiError::Io(path, e) => write!(f, "Could not read {}: {e}", path.display()),
+            UiError::Parse(Some(path), e) => write!(f, "Invalid {}: {e}", path.display()),
+            UiError::Parse(None, e) => write!(f, "Invalid UI description: {e}"),
+            UiError::InvalidImage(path) => write!(f, "Could not decode {}", path.display()),
+            UiError::InvalidColor(value) => write!(f, "Invalid color: {value}"),
+            UiError::UnknownStyle(name) => write!(f, "Unknown style: {name}"),
+        }
+    }
+}
+
+impl std::error::Error for UiError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            UiError::Io(_, e) => Some(e),
+            UiError::Parse(_, e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors_are_parsed_with_and_without_alpha() {
+        assert_eq!(
+            parse_color("#1a2B3c").unwrap(),
+            Color::from_argb(0xff, 0x1a, 0x2b, 0x3c)
+        );
+        assert_eq!(
+            parse_color("#1a2b3c80").unwrap(),
+            Color::from_argb(0x80, 0x1a, 0x2b, 0x3c)
+        );
+    }
+
+    #[test]
+    fn invalid_colors_are_rejected() {
+        for value in [
+            "1a2b3c", "#1a2b3", "#1a2b3c4", "#1a2b3g", "#+a2b3c", "#-1a2b3c", "#ééé",
+        ] {
+            assert!(
+                matches!(parse_color(value), Err(UiError::InvalidColor(ref v)) if v == value),
+                "{value} should be invalid"
+            );
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Debug, Formatter};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::{fmt, mem};
+use winit::window::WindowId;
+
+/// A unique key of a [`Widget`](crate::ui::Widget), used to track which widgets depend on which [`Signal`]s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WidgetKey(u64);
+
+/// An observable value.
+///
+/// Reading the value with [`get`](Signal::get) or [`with`](Signal::with) while a window
+/// is being drawn subscribes the widget being drawn to the signal. Changing the value
+/// with [`set`](Signal::set) or [`update`](Signal::update) marks all the subscribed widgets
+/// as dirty and redraws their windows. Subscriptions only last until the next change,
+/// because the next drawing subscribes the widgets again.
+///
+/// Signals are cheap to clone; all clones share the same value.
+pub struct Signal<T> {
+    inner: Rc<SignalInner<T>>,
+}
+
+struct SignalInner<T> {
+    value: RefCell<T>,
+    subscribers: RefCell<HashSet<Subscriber>>,
+}
+
+/// A named collection of text [`Signal`]s that [UI descriptions](crate::ui::UiDescription)
+/// can bind labels and buttons to.
+///
+/// Stores are cheap to clone; all clones share the same signals.
+#[derive(Clone, Default)]
+pub struct Store {
+    signals: Rc<RefCell<HashMap<String, Signal<String>>>>,
+}
+
+/// A widget in a window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Subscriber {
+    window: WindowId,
+    widget: Option<WidgetKey>,
+}
+
+thread_local! {
+    /// The window and widget currently being drawn.
+    static TRACKED_WINDOW: Cell<Option<WindowId>> = const { Cell::new(None) };
+    static TRACKED_WIDGET: Cell<Option<WidgetKey>> = const { Cell::new(None) };
+    /// Widgets that have been invalidated since the last call to [`take_invalidated`], by window.
+    static INVALIDATED: RefCell<HashMap<WindowId, HashSet<WidgetKey>>> = RefCell::new(HashMap::new());
+}
+
+impl WidgetKey {
+    /// Generates a new, unique key.
+    pub(crate) fn next() -> Self {
+        static NEXT_KEY: AtomicU64 = AtomicU64::new(0);
+        WidgetKey(NEXT_KEY.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl<T> Signal<T> {
+    /// Creates a new signal with an initial `value`.
+    pub fn new(value: T) -> Self {
+        Signal {
+            inner: Rc::new(SignalInner {
+                value: RefCell::new(value),
+                subscribers: RefCell::new(HashSet::new()),
+            }),
+        }
+    }
+
+    /// Returns a copy of the value, subscribing the widget being drawn, if any.
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.with(T::clone)
+    }
+
+    /// Calls `f` with a reference to the value, subscribing the widget being drawn, if any.
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        if let Some(window) = TRACKED_WINDOW.get() {
+            let widget = TRACKED_WIDGET.get();
+            self.inner
+                .subscribers
+                .borrow_mut()
+                .insert(Subscriber { window, widget });
+        }
+        f(&self.inner.value.borrow())
+    }
+
+    /// Calls `f` with a reference to the value, without subscribing to the signal.
+    pub fn with_untracked<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.inner.value.borrow())
+    }
+
+    /// Replaces the value and invalidates all the subscribers.
+    pub fn set(&self, value: T) {
+        self.update(|current| *current = value);
+    }
+
+    /// Modifies the value in place and invalidates all the subscribers.
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        f(&mut self.inner.value.borrow_mut());
+        let subscribers = mem::take(&mut *self.inner.subscribers.borrow_mut());
+        INVALIDATED.with_borrow_mut(|invalidated| {
+            for Subscriber { window, widget } in subscribers {
+                let widgets = invalidated.entry(window).or_default();
+                widgets.extend(widget);
+            }
+        });
+    }
+}
+
+impl<T> Clone for Signal<T> {
+    fn clone(&self) -> Self {
+        Signal {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Debug> Debug for Signal<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Signal")
+            .field(&*self.inner.value.borrow())
+            .finish()
+    }
+}
+
+impl<T: Default> Default for Signal<T> {
+    fn default() -> Self {
+        Signal::new(T::default())
+    }
+}
+
+impl Store {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Store::default()
+    }
+
+    /// Returns the signal with the given `name`, creating an empty one if it does not exist yet.
+    pub fn signal(&self, name: &str) -> Signal<String> {
+        self.signals
+            .borrow_mut()
+            .entry(name.to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// Sets the value of the signal with the given `name`.
+    pub fn set(&self, name: &str, value: impl Into<String>) {
+        self.signal(name).set(value.into());
+    }
+}
+
+impl Debug for Store {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.signals.borrow().iter()).finish()
+    }
+}
+
+/// Calls `f`, subscribing all the signals read inside it to the window with the given `window_id`.
+pub(crate) fn track_window<R>(window_id: WindowId, f: impl FnOnce() -> R) -> R {
+    let previous = TRACKED_WINDOW.replace(Some(window_id));
+    let result = f();
+    TRACKED_WINDOW.set(previous);
+    result
+}
+
+/// Calls `f`, subscribing all the signals read inside it to the widget with the given `key`.
+pub(crate) fn track_widget<R>(key: WidgetKey, f: impl FnOnce() -> R) -> R {
+    let previous = TRACKED_WIDGET.replace(Some(key));
+    let result = f();
+    TRACKED_WIDGET.set(previous);
+    result
+}
+
+/// Returns the widgets invalidated since the last call, grouped by their windows.
+///
+/// A window can be invalidated without any widgets if a signal was read outside of a widget.
+pub(crate) fn take_invalidated() -> HashMap<WindowId, HashSet<WidgetKey>> {
+    INVALIDATED.take()
+}
@@ -0,0 +1,224 @@
+use std::ops::Range;
+
+/// The text and selection of a [text input widget](crate::ui::WidgetKind::TextInput).
+///
+/// Positions are byte offsets in the text, always on character boundaries.
+/// The selection goes from the anchor, where it was started, to the cursor, and is empty
+/// when they are at the same position.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TextInput {
+    text: String,
+    cursor: usize,
+    anchor: usize,
+}
+
+impl TextInput {
+    /// Creates a text input containing `text`, with the cursor at its end.
+    pub fn new(text: impl Into<String>) -> Self {
+        let text = text.into();
+        let end = text.len();
+        TextInput {
+            text,
+            cursor: end,
+            anchor: end,
+        }
+    }
+
+    /// Returns the text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Replaces the text, moving the cursor to its end.
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        *self = TextInput::new(text);
+    }
+
+    /// Returns the position of the cursor.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Returns the range of the selected text, which is empty if nothing is selected.
+    pub fn selection(&self) -> Range<usize> {
+        self.cursor.min(self.anchor)..self.cursor.max(self.anchor)
+    }
+
+    /// Returns the selected text, if any.
+    pub fn selected_text(&self) -> Option<&str> {
+        let selection = self.selection();
+        (!selection.is_empty()).then(|| &self.text[selection])
+    }
+
+    /// Moves the cursor to `position`, clamped to the text and moved back to a character boundary,
+    /// extending the selection if `select` is `true` or removing it otherwise.
+    pub fn set_cursor(&mut self, position: usize, select: bool) {
+        let mut position = position.min(self.text.len());
+        while !self.text.is_char_boundary(position) {
+            position -= 1;
+        }
+        self.cursor = position;
+        if !select {
+            self.anchor = position;
+        }
+    }
+
+    /// Selects the whole text.
+    pub fn select_all(&mut self) {
+        self.anchor = 0;
+        self.cursor = self.text.len();
+    }
+
+    /// Moves the cursor one character to the left. Without `select`, a selection collapses to its start instead.
+    pub fn move_left(&mut self, select: bool) {
+        let selection = self.selection();
+        let position = match select || selection.is_empty() {
+            true => self.previous_boundary(),
+            false => selection.start,
+        };
+        self.set_cursor(position, select);
+    }
+
+    /// Moves the cursor one character to the right. Without `select`, a selection collapses to its end instead.
+    pub fn move_right(&mut self, select: bool) {
+        let selection = self.selection();
+        let position = match select || selection.is_empty() {
+            true => self.next_boundary(),
+            false => selection.end,
+        };
+        self.set_cursor(position, select);
+    }
+
+    /// Moves the cursor to the start of the text.
+    pub fn move_home(&mut self, select: bool) {
+        self.set_cursor(0, select);
+    }
+
+    /// Moves the cursor to the end of the text.
+    pub fn move_end(&mut self, select: bool) {
+        self.set_cursor(self.text.len(), select);
+    }
+
+    /// Replaces the selection with `text`, leaving out control characters such as line breaks,
+    /// and moves the cursor after it.
+    pub fn insert(&mut self, text: &str) {
+        let text: String = text.chars().filter(|c| !c.is_control()).collect();
+        let selection = self.selection();
+        self.text.replace_range(selection.clone(), &text);
+        self.set_cursor(selection.start + text.len(), false);
+    }
+
+    /// Deletes the selection, or the character before the cursor if nothing is selected.
+    pub fn delete_backward(&mut self) {
+        if self.selection().is_empty() {
+            self.anchor = self.previous_boundary();
+        }
+        self.insert("");
+    }
+
+    /// Deletes the selection, or the character after the cursor if nothing is selected.
+    pub fn delete_forward(&mut self) {
+        if self.selection().is_empty() {
+            self.anchor = self.next_boundary();
+        }
+        self.insert("");
+    }
+
+    /// Removes the selected text and returns it, if any.
+    pub fn cut(&mut self) -> Option<String> {
+        let selected = self.selected_text()?.to_string();
+        self.insert("");
+        Some(selected)
+    }
+
+    fn previous_boundary(&self) -> usize {
+        self.text[..self.cursor]
+            .char_indices()
+            .next_back()
+            .map_or(0, |(i, _)| i)
+    }
+
+    fn next_boundary(&self) -> usize {
+        self.text[self.cursor..]
+            .chars()
+            .next()
+            .map_or(self.cursor, |c| self.cursor + c.len_utf8())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typed_text_replaces_the_selection() {
+        let mut input = TextInput::new("Hello world");
+        input.set_cursor(6, false);
+        input.set_cursor(11, true);
+        assert_eq!(input.selected_text(), Some("world"));
+
+        input.insert("Ferris");
+
+        assert_eq!(input.text(), "Hello Ferris");
+        assert_eq!(input.cursor(), 12);
+        assert_eq!(input.selected_text(), None);
+    }
+
+    #[test]
+    fn inserted_text_leaves_out_control_characters() {
+        let mut input = TextInput::new("");
+        input.insert("one\ntwo\t");
+        assert_eq!(input.text(), "onetwo");
+    }
+
+    #[test]
+    fn the_cursor_moves_over_whole_characters() {
+        let mut input = TextInput::new("añb");
+        input.move_left(false);
+        input.move_left(false);
+        assert_eq!(input.cursor(), 1);
+        input.move_right(true);
+        assert_eq!(input.selected_text(), Some("ñ"));
+
+        input.set_cursor(2, false);
+        assert_eq!(input.cursor(), 1);
+    }
+
+    #[test]
+    fn moving_without_selecting_collapses_the_selection() {
+        let mut input = TextInput::new("abcd");
+        input.select_all();
+        input.move_left(false);
+        assert_eq!((input.cursor(), input.selected_text()), (0, None));
+
+        input.select_all();
+        input.move_right(false);
+        assert_eq!((input.cursor(), input.selected_text()), (4, None));
+    }
+
+    #[test]
+    fn deleting_removes_the_selection_or_one_character() {
+        let mut input = TextInput::new("añb");
+        input.move_left(false);
+        input.delete_backward();
+        assert_eq!(input.text(), "ab");
+        input.move_home(false);
+        input.delete_forward();
+        assert_eq!(input.text(), "b");
+        input.select_all();
+        input.delete_backward();
+        assert_eq!(input.text(), "");
+        input.delete_backward();
+        assert_eq!((input.text(), input.cursor()), ("", 0));
+    }
+
+    #[test]
+    fn cutting_removes_and_returns_the_selection() {
+        let mut input = TextInput::new("copy me");
+        assert_eq!(input.cut(), None);
+
+        input.set_cursor(5, true);
+        assert_eq!(input.cut().as_deref(), Some("me"));
+        assert_eq!(input.text(), "copy ");
+    }
+}
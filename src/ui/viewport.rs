@@ -0,0 +1,229 @@
+use serde::Deserialize;
+use skia_safe::{Matrix, Point, Rect, Vector};
+
+/// Space, in logical pixels, left between the content and the edges of a viewport zoomed to fit it.
+const FIT_MARGIN: f32 = 16.0;
+
+/// The pan and zoom of a [viewport widget](crate::ui::WidgetKind::Viewport), mapping its content to the window.
+///
+/// Content coordinates are the logical pixels the viewport's children are laid out and painted in,
+/// and window coordinates are the logical pixels of the window, in which inputs are reported.
+/// The window's scale factor maps them to physical pixels on top of the viewport's transform,
+/// as in [`device_matrix`](Viewport::device_matrix).
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Viewport {
+    /// The smallest zoom, as a ratio of content pixels to window pixels.
+    pub min_zoom: f32,
+    /// The largest zoom, as a ratio of content pixels to window pixels.
+    pub max_zoom: f32,
+    #[serde(skip)]
+    zoom: f32,
+    #[serde(skip)]
+    offset: Vector,
+    #[serde(skip)]
+    bounds: Rect,
+}
+
+impl Viewport {
+    /// Creates a viewport showing its content at its original size, from its top-left corner.
+    pub fn new() -> Self {
+        Viewport::default()
+    }
+
+    /// Limits the zoom to the range from `min_zoom` to `max_zoom`.
+    pub fn with_zoom_range(mut self, min_zoom: f32, max_zoom: f32) -> Self {
+        self.min_zoom = min_zoom;
+        self.max_zoom = max_zoom.max(min_zoom);
+        self.zoom = self.clamp_zoom(self.zoom);
+        self
+    }
+
+    /// Returns the zoom, as a ratio of content pixels to window pixels.
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    /// Returns the position of the content's origin relative to the viewport's top-left corner, in window pixels.
+    pub fn offset(&self) -> Vector {
+        self.offset
+    }
+
+    /// Returns the bounds of the viewport in the window, assigned during the last layout.
+    pub fn bounds(&self) -> Rect {
+        self.bounds
+    }
+
+    /// Returns the transform from content coordinates to window coordinates.
+    pub fn matrix(&self) -> Matrix {
+        let mut matrix =
+            Matrix::translate(Point::new(self.bounds.left, self.bounds.top) + self.offset);
+        matrix.pre_scale((self.zoom, self.zoom), None);
+        matrix
+    }
+
+    /// Returns the transform from content coordinates to the physical pixels of a window
+    /// with the given `scale_factor`, as applied when the content is painted.
+    pub fn device_matrix(&self, scale_factor: f32) -> Matrix {
+        let mut matrix = Matrix::scale((scale_factor, scale_factor));
+        matrix.pre_concat(&self.matrix());
+        matrix
+    }
+
+    /// Converts a `point` in window coordinates to content coordinates.
+    pub fn to_content(&self, point: Point) -> Point {
+        let origin = Point::new(self.bounds.left, self.bounds.top) + self.offset;
+        let offset = point - origin;
+        Point::new(offset.x / self.zoom, offset.y / self.zoom)
+    }
+
+    /// Converts a `point` in content coordinates to window coordinates.
+    pub fn to_window(&self, point: Point) -> Point {
+        self.matrix().map_point(point)
+    }
+
+    /// Converts a `rect` in content coordinates to window coordinates.
+    pub fn rect_to_window(&self, rect: Rect) -> Rect {
+        self.matrix().map_rect(rect).0
+    }
+
+    /// Moves the content by `delta`, in window pixels.
+    pub fn pan_by(&mut self, delta: Vector) {
+        self.offset += delta;
+    }
+
+    /// Sets the zoom, clamped to the [zoom range](Viewport::with_zoom_range), keeping the content
+    /// under `anchor`, in window coordinates, in place.
+    pub fn set_zoom(&mut self, zoom: f32, anchor: Point) {
+        let content = self.to_content(anchor);
+        self.zoom = self.clamp_zoom(zoom);
+        self.pan_by(anchor - self.to_window(content));
+    }
+
+    /// Multiplies the zoom by `factor`, keeping the content under `anchor`, in window coordinates, in place.
+    pub fn zoom_by(&mut self, factor: f32, anchor: Point) {
+        self.set_zoom(self.zoom * factor, anchor);
+    }
+
+    /// Zooms and pans so that `content`, in content coordinates, fills the viewport and is centered in it.
+    pub fn zoom_to_fit(&mut self, content: Rect) {
+        let available = self.bounds.with_inset((FIT_MARGIN, FIT_MARGIN));
+        if content.is_empty() || available.is_empty() {
+            return;
+        }
+        let zoom = (available.width() / content.width()).min(available.height() / content.height());
+        self.zoom = self.clamp_zoom(zoom);
+        let center = Point::new(self.bounds.width() / 2.0, self.bounds.height() / 2.0);
+        self.offset = center - Point::new(content.center_x(), content.center_y()) * self.zoom;
+    }
+
+    /// Shows the content at its original size, from its top-left corner.
+    pub fn reset(&mut self) {
+        self.zoom = self.clamp_zoom(1.0);
+        self.offset = Vector::default();
+    }
+
+    /// Assigns the viewport its `bounds` in the window.
+    pub(crate) fn set_bounds(&mut self, bounds: Rect) {
+        self.bounds = bounds;
+    }
+
+    fn clamp_zoom(&self, zoom: f32) -> f32 {
+        // Unlike `clamp`, this doesn't panic if a description has a minimum larger than the maximum.
+        if zoom.is_finite() {
+            zoom.max(self.min_zoom).min(self.max_zoom)
+        } else {
+            self.zoom
+        }
+    }
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Viewport {
+            min_zoom: 0.1,
+            max_zoom: 10.0,
+            zoom: 1.0,
+            offset: Vector::default(),
+            bounds: Rect::new_empty(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn viewport(bounds: Rect) -> Viewport {
+        let mut viewport = Viewport::new();
+        viewport.set_bounds(bounds);
+        viewport
+    }
+
+    #[test]
+    fn points_are_converted_between_content_and_window() {
+        let mut viewport = viewport(Rect::from_xywh(100.0, 50.0, 300.0, 250.0));
+        viewport.pan_by(Vector::new(10.0, 20.0));
+        // Zooming around the content's origin keeps it in place.
+        viewport.set_zoom(2.0, Point::new(110.0, 70.0));
+
+        assert_eq!(
+            viewport.to_window(Point::new(0.0, 0.0)),
+            Point::new(110.0, 70.0)
+        );
+        assert_eq!(
+            viewport.to_window(Point::new(5.0, 5.0)),
+            Point::new(120.0, 80.0)
+        );
+        assert_eq!(
+            viewport.to_content(Point::new(120.0, 80.0)),
+            Point::new(5.0, 5.0)
+        );
+        assert_eq!(
+            viewport.rect_to_window(Rect::from_xywh(0.0, 0.0, 10.0, 10.0)),
+            Rect::from_xywh(110.0, 70.0, 20.0, 20.0)
+        );
+    }
+
+    #[test]
+    fn zooming_keeps_the_anchor_in_place() {
+        let mut viewport = viewport(Rect::from_wh(300.0, 200.0));
+        let anchor = Point::new(150.0, 100.0);
+        let content = viewport.to_content(anchor);
+
+        viewport.zoom_by(4.0, anchor);
+
+        assert_eq!(viewport.zoom(), 4.0);
+        assert_eq!(viewport.to_window(content), anchor);
+    }
+
+    #[test]
+    fn zoom_stays_in_range() {
+        let mut viewport = viewport(Rect::from_wh(300.0, 200.0)).with_zoom_range(0.5, 4.0);
+        viewport.set_zoom(10.0, Point::default());
+        assert_eq!(viewport.zoom(), 4.0);
+        viewport.set_zoom(0.1, Point::default());
+        assert_eq!(viewport.zoom(), 0.5);
+        viewport.set_zoom(f32::NAN, Point::default());
+        assert_eq!(viewport.zoom(), 0.5);
+    }
+
+    #[test]
+    fn zooming_to_fit_centers_the_content_within_the_margin() {
+        let mut viewport = viewport(Rect::from_wh(232.0, 132.0));
+        let content = Rect::from_xywh(0.0, 0.0, 400.0, 100.0);
+
+        viewport.zoom_to_fit(content);
+
+        assert_eq!(viewport.zoom(), 0.5);
+        assert_eq!(
+            viewport.to_window(Point::new(200.0, 50.0)),
+            Point::new(116.0, 66.0)
+        );
+        assert_eq!(viewport.to_window(Point::new(0.0, 0.0)).x, FIT_MARGIN);
+
+        viewport.reset();
+        assert_eq!(viewport.zoom(), 1.0);
+        assert_eq!(viewport.offset(), Vector::default());
+    }
+}
@@ -0,0 +1,1018 @@
+use crate::ui::state::track_widget;
+use crate::ui::{
+    default_font, DragPayload, DropTarget, GestureRecognizer, Signal, TextInput, Viewport,
+    WidgetKey,
+};
+
+use serde::Deserialize;
+use skia_safe::{
+    svg, Canvas, Color, Contains, CubicResampler, FilterMode, Image, MipmapMode, Paint, PaintStyle,
+    Point, QuickReject, Rect, SamplingOptions,
+};
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+const DEFAULT_FONT_SIZE: f32 = 14.0;
+const DEFAULT_CORNER_RADIUS: f32 = 4.0;
+const BUTTON_BACKGROUND: Color = Color::from_rgb(0xe0, 0xe0, 0xe0);
+const DROP_HIGHLIGHT: Color = Color::from_rgb(0x3a, 0x6e, 0xa5);
+const TEXT_INPUT_BORDER: Color = Color::from_rgb(0xa0, 0xa0, 0xa0);
+const FOCUS_BORDER: Color = Color::from_rgb(0x3a, 0x6e, 0xa5);
+const SELECTION: Color = Color::from_argb(0x60, 0x3a, 0x6e, 0xa5);
+
+/// A node of a window's widget tree.
+///
+/// All sizes and positions are in logical pixels.
+#[derive(Debug, Clone)]
+pub struct Widget {
+    key: WidgetKey,
+    id: Option<String>,
+    kind: WidgetKind,
+    layout: Layout,
+    style: Style,
+    action: Option<String>,
+    hit_region: HitRegion,
+    drop_target: Option<DropTarget>,
+    drop_highlighted: bool,
+    focused: bool,
+    drag_payload: Option<DragPayload>,
+    gestures: Vec<GestureRecognizer>,
+    children: Vec<Widget>,
+    text_binding: Option<Signal<String>>,
+    bounds: Rect,
+    painted_bounds: Option<Rect>,
+    dirty: bool,
+}
+
+/// The type of a [`Widget`], along with its content.
+#[derive(Debug, Clone)]
+pub enum WidgetKind {
+    /// A container laying its children out from top to bottom.
+    Column,
+    /// A container laying its children out from left to right.
+    Row,
+    /// A line of text.
+    Label(String),
+    /// A clickable line of text triggering the widget's action.
+    Button(String),
+    /// An image, stretched to fill the widget's content.
+    Image(ImageContent),
+    /// An empty space, usually combined with [`Layout::flex`].
+    Spacer,
+    /// A pannable and zoomable area laying its children out from top to bottom in content coordinates,
+    /// which are mapped to the window by the [`Viewport`]'s transform.
+    /// It has no preferred size, so it should be given a fixed size or [`Layout::flex`].
+    Viewport(Viewport),
+    /// A single line of editable text, focused by clicking it.
+    TextInput(TextInput),
+}
+
+/// The content of an [image widget](WidgetKind::Image).
+#[derive(Debug, Clone)]
+pub enum ImageContent {
+    /// A decoded raster image.
+    Raster(Image),
+    /// A parsed SVG document. It has no preferred size, so the widget's
+    /// [`Layout::width`] and [`Layout::height`] should be set.
+    Svg(svg::Dom),
+}
+
+/// The part of a window a [`Widget`] stands for when it is pressed,
+/// for windows drawing their own title bar and borders instead of the OS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+pub enum HitRegion {
+    /// Regular content, receiving input.
+    #[default]
+    Client,
+    /// A title bar. Dragging it moves the window, and double-clicking it maximizes or restores the window.
+    Caption,
+    /// A border. Dragging it resizes the window at the given edge.
+    Resize(ResizeEdge),
+    /// A see-through area of an overlay. Clicks go through it to the windows below.
+    PassThrough,
+}
+
+/// An edge or corner of a window, for resizing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[allow(missing_docs)]
+pub enum ResizeEdge {
+    Top,
+    Bottom,
+    Left,
+    Right,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// Layout properties of a [`Widget`].
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Layout {
+    /// Fixed width. If `None`, the widget is stretched in a [column](WidgetKind::Column)
+    /// and uses its preferred width in a [row](WidgetKind::Row).
+    pub width: Option<f32>,
+    /// Fixed height. If `None`, the widget is stretched in a [row](WidgetKind::Row)
+    /// and uses its preferred height in a [column](WidgetKind::Column).
+    pub height: Option<f32>,
+    /// Share of the parent's remaining space along its main axis.
+    /// Widgets with `0.0` use their fixed or preferred size instead.
+    pub flex: f32,
+    /// Space between the widget's bounds and its content.
+    pub padding: f32,
+    /// Space between the widget's children.
+    pub spacing: f32,
+}
+
+/// Visual properties of a [`Widget`]. Unset properties fall back to the widget's defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Style {
+    /// Background color.
+    pub background: Option<Color>,
+    /// Text color.
+    pub foreground: Option<Color>,
+    /// Border color.
+    pub border: Option<Color>,
+    /// Radius of the background's and border's corners.
+    pub corner_radius: Option<f32>,
+    /// Size of the text.
+    pub font_size: Option<f32>,
+}
+
+impl Widget {
+    /// Creates a new widget of the given `kind` with default layout and style.
+    pub fn new(kind: WidgetKind) -> Self {
+        Widget {
+            key: WidgetKey::next(),
+            id: None,
+            kind,
+            layout: Layout::default(),
+            style: Style::default(),
+            action: None,
+            hit_region: HitRegion::Client,
+            drop_target: None,
+            drop_highlighted: false,
+            focused: false,
+            drag_payload: None,
+            gestures: Vec::new(),
+            children: Vec::new(),
+            text_binding: None,
+            bounds: Rect::new_empty(),
+            painted_bounds: None,
+            dirty: true,
+        }
+    }
+
+    /// Sets the widget's ID, used to [find](Widget::find) it in the tree.
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Sets the widget's layout properties.
+    pub fn with_layout(mut self, layout: Layout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Sets the widget's style.
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the name of the action triggered when the widget is clicked.
+    pub fn with_action(mut self, action: impl Into<String>) -> Self {
+        self.action = Some(action.into());
+        self
+    }
+
+    /// Sets the part of the window the widget stands for when it is pressed.
+    ///
+    /// Children inherit it, except the ones with an action or their own region.
+    pub fn with_hit_region(mut self, region: HitRegion) -> Self {
+        self.hit_region = region;
+        self
+    }
+
+    /// Makes the widget accept files dragged from other applications.
+    pub fn with_drop_target(mut self, drop_target: DropTarget) -> Self {
+        self.drop_target = Some(drop_target);
+        self
+    }
+
+    /// Makes the widget draggable to the [drop targets](Widget::with_drop_target) accepting the `payload`.
+    pub fn with_drag_payload(mut self, payload: DragPayload) -> Self {
+        self.drag_payload = Some(payload);
+        self
+    }
+
+    /// Makes the widget recognize a kind of gesture started on it with touches or a touchpad.
+    pub fn with_gesture(mut self, recognizer: GestureRecognizer) -> Self {
+        self.gestures.push(recognizer);
+        self
+    }
+
+    /// Appends a child to the widget.
+    pub fn with_child(mut self, child: Widget) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// Binds the text of a [label](WidgetKind::Label) or a [button](WidgetKind::Button)
+    /// to a `signal`, replacing its static text.
+    ///
+    /// Whenever the signal changes, the widget is marked as [dirty](Widget::is_dirty)
+    /// and its window is redrawn. [Text inputs](WidgetKind::TextInput) write their text
+    /// to the signal instead whenever it is [edited](Widget::edit_text).
+    pub fn with_text_binding(mut self, signal: Signal<String>) -> Self {
+        self.text_binding = Some(signal);
+        self
+    }
+
+    /// Returns the widget's unique key.
+    pub fn key(&self) -> WidgetKey {
+        self.key
+    }
+
+    /// Returns the widget's ID.
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    /// Returns the widget's type and content.
+    pub fn kind(&self) -> &WidgetKind {
+        &self.kind
+    }
+
+    /// Returns a mutable reference to the widget's type and content, marking the widget as dirty.
+    pub fn kind_mut(&mut self) -> &mut WidgetKind {
+        self.dirty = true;
+        &mut self.kind
+    }
+
+    /// Returns the name of the action triggered when the widget is clicked.
+    pub fn action(&self) -> Option<&str> {
+        self.action.as_deref()
+    }
+
+    /// Returns the part of the window the widget stands for when it is pressed.
+    pub fn hit_region(&self) -> HitRegion {
+        self.hit_region
+    }
+
+    /// Returns the files the widget accepts, if it is a drop target.
+    pub fn drop_target(&self) -> Option<&DropTarget> {
+        self.drop_target.as_ref()
+    }
+
+    /// Returns the payload dragged from the widget, if it is draggable.
+    pub fn drag_payload(&self) -> Option<&DragPayload> {
+        self.drag_payload.as_ref()
+    }
+
+    /// Returns the widget's gesture recognizers.
+    pub fn gestures(&self) -> &[GestureRecognizer] {
+        &self.gestures
+    }
+
+    /// Returns the pan and zoom of the widget, if it is a [viewport](WidgetKind::Viewport).
+    pub fn viewport(&self) -> Option<&Viewport> {
+        match &self.kind {
+            WidgetKind::Viewport(viewport) => Some(viewport),
+            _ => None,
+        }
+    }
+
+    /// Changes the pan and zoom of the widget with `update`, if it is a [viewport](WidgetKind::Viewport),
+    /// and marks it as dirty if they have changed.
+    pub fn update_viewport(&mut self, update: impl FnOnce(&mut Viewport)) {
+        if let WidgetKind::Viewport(viewport) = &mut self.kind {
+            let old = *viewport;
+            update(viewport);
+            if *viewport != old {
+                self.dirty = true;
+            }
+        }
+    }
+
+    /// Returns the text and selection of the widget, if it is a [text input](WidgetKind::TextInput).
+    pub fn text_input(&self) -> Option<&TextInput> {
+        match &self.kind {
+            WidgetKind::TextInput(input) => Some(input),
+            _ => None,
+        }
+    }
+
+    /// Changes the text or selection of the widget with `edit`, if it is a [text input](WidgetKind::TextInput),
+    /// marks it as dirty if they have changed, and writes the text to its [binding](Widget::with_text_binding)
+    /// if the text has changed.
+    pub fn edit_text<R>(&mut self, edit: impl FnOnce(&mut TextInput) -> R) -> Option<R> {
+        let input = match &mut self.kind {
+            WidgetKind::TextInput(input) => input,
+            _ => return None,
+        };
+        let old = input.clone();
+        let result = edit(input);
+        if *input != old {
+            self.dirty = true;
+        }
+        if input.text() != old.text() {
+            if let Some(signal) = &self.text_binding {
+                signal.set(input.text().to_string());
+            }
+        }
+        Some(result)
+    }
+
+    /// Returns the position in the text of the widget, if it is a [text input](WidgetKind::TextInput),
+    /// of the character boundary closest to `x`, in the coordinates the widget is laid out in.
+    pub fn text_position_at(&self, x: f32) -> Option<usize> {
+        let input = self.text_input()?;
+        let font = default_font(self.font_size());
+        let x = x - (self.bounds.left + self.layout.padding);
+        let boundaries = input.text().char_indices().map(|(i, _)| i);
+        let position = boundaries
+            .chain([input.text().len()])
+            .min_by(|&a, &b| {
+                let distance = |i: usize| (font.measure_str(&input.text()[..i], None).0 - x).abs();
+                distance(a).total_cmp(&distance(b))
+            })
+            .unwrap_or(0);
+        Some(position)
+    }
+
+    /// Returns `true` if the widget has the keyboard focus.
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Zooms and pans the widget, if it is a [viewport](WidgetKind::Viewport), so that all of its children are visible.
+    pub fn zoom_to_fit(&mut self) {
+        let content = self.children.iter().map(Widget::bounds).reduce(Rect::join2);
+        if let Some(content) = content {
+            self.update_viewport(|viewport| viewport.zoom_to_fit(content));
+        }
+    }
+
+    /// Returns the widget's style.
+    pub fn style(&self) -> &Style {
+        &self.style
+    }
+
+    /// Returns the widget's children.
+    pub fn children(&self) -> &[Widget] {
+        &self.children
+    }
+
+    /// Returns the bounds assigned to the widget during the last [layout](Widget::layout).
+    ///
+    /// They are in the window's coordinates, except inside [viewports](WidgetKind::Viewport),
+    /// where they are in the viewport's content coordinates, see [`window_bounds`](Widget::window_bounds).
+    pub fn bounds(&self) -> Rect {
+        self.bounds
+    }
+
+    /// Returns the bounds of the widget with the given `key` in this widget's subtree, mapped through
+    /// the [viewports](WidgetKind::Viewport) containing it to the coordinates this widget is laid out in,
+    /// which are the window's coordinates for the root of a window's widget tree.
+    pub fn window_bounds(&self, key: WidgetKey) -> Option<Rect> {
+        if self.key == key {
+            return Some(self.bounds);
+        }
+        let bounds = self
+            .children
+            .iter()
+            .find_map(|child| child.window_bounds(key))?;
+        Some(match &self.kind {
+            WidgetKind::Viewport(viewport) => viewport.rect_to_window(bounds),
+            _ => bounds,
+        })
+    }
+
+    /// Returns `true` if the widget has changed since it was last painted.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Marks the widgets with the given `keys` in this widget's subtree as dirty.
+    pub fn mark_dirty(&mut self, keys: &HashSet<WidgetKey>) {
+        if keys.contains(&self.key) {
+            self.dirty = true;
+        }
+        for child in &mut self.children {
+            child.mark_dirty(keys);
+        }
+    }
+
+    /// Marks this widget and all of its descendants as clean, remembering where they were painted.
+    pub fn mark_clean(&mut self) {
+        self.dirty = false;
+        self.painted_bounds = Some(self.bounds);
+        for child in &mut self.children {
+            child.mark_clean();
+        }
+    }
+
+    /// Returns the area that has to be repainted since the tree was last painted, if any.
+    ///
+    /// It covers the old and new bounds of all the dirty widgets and of the widgets
+    /// that have been moved or resized by the last [layout](Widget::layout).
+    pub fn damage(&self) -> Option<Rect> {
+        let mut damage = None;
+        self.collect_damage(&mut damage);
+        damage
+    }
+
+    fn collect_damage(&self, damage: &mut Option<Rect>) {
+        if self.dirty || self.painted_bounds != Some(self.bounds) {
+            // The whole subtree is repainted within the widget's bounds.
+            for bounds in self.painted_bounds.into_iter().chain([self.bounds]) {
+                match damage {
+                    Some(damage) => damage.join(bounds),
+                    None => *damage = Some(bounds),
+                }
+            }
+            return;
+        }
+        if let WidgetKind::Viewport(viewport) = &self.kind {
+            // The children's damage is in content coordinates, and only visible within the viewport.
+            let mut content_damage = None;
+            for child in &self.children {
+                child.collect_damage(&mut content_damage);
+            }
+            let mut visible = match content_damage {
+                Some(content_damage) => viewport.rect_to_window(content_damage),
+                None => return,
+            };
+            if visible.intersect(self.bounds) {
+                match damage {
+                    Some(damage) => damage.join(visible),
+                    None => *damage = Some(visible),
+                }
+            }
+            return;
+        }
+        for child in &self.children {
+            child.collect_damage(damage);
+        }
+    }
+
+    /// Finds the widget with the given `id` in this widget's subtree.
+    pub fn find(&self, id: &str) -> Option<&Widget> {
+        if self.id() == Some(id) {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(id))
+    }
+
+    /// Finds the widget with the given `id` in this widget's subtree.
+    pub fn find_mut(&mut self, id: &str) -> Option<&mut Widget> {
+        if self.id() == Some(id) {
+            return Some(self);
+        }
+        self.children
+            .iter_mut()
+            .find_map(|child| child.find_mut(id))
+    }
+
+    /// Returns the deepest widget containing the `point`.
+    pub fn hit_test(&self, point: Point) -> Option<&Widget> {
+        if !self.bounds.contains(point) {
+            return None;
+        }
+        let point = self.content_point(point);
+        self.children
+            .iter()
+            .find_map(|child| child.hit_test(point))
+            .or(Some(self))
+    }
+
+    /// Returns the action of the deepest widget containing the `point` that has one.
+    pub fn action_at(&self, point: Point) -> Option<&str> {
+        if !self.bounds.contains(point) {
+            return None;
+        }
+        let point = self.content_point(point);
+        self.children
+            .iter()
+            .find_map(|child| child.action_at(point))
+            .or(self.action())
+    }
+
+    /// Returns the region of the deepest widget containing the `point` that has an action or its own
+    /// [hit region](Widget::with_hit_region). Widgets with an action are always [client](HitRegion::Client) areas.
+    pub fn region_at(&self, point: Point) -> Option<HitRegion> {
+        if !self.bounds.contains(point) {
+            return None;
+        }
+        let own = match (&self.action, self.hit_region) {
+            (Some(_), _) => Some(HitRegion::Client),
+            (None, HitRegion::Client) => None,
+            (None, region) => Some(region),
+        };
+        let point = self.content_point(point);
+        self.children
+            .iter()
+            .find_map(|child| child.region_at(point))
+            .or(own)
+    }
+
+    /// Returns the deepest [drop target](Widget::with_drop_target) containing the `point`.
+    pub fn drop_target_at(&self, point: Point) -> Option<&Widget> {
+        if !self.bounds.contains(point) {
+            return None;
+        }
+        let point = self.content_point(point);
+        self.children
+            .iter()
+            .find_map(|child| child.drop_target_at(point))
+            .or(self.drop_target.as_ref().map(|_| self))
+    }
+
+    /// Returns the deepest [draggable](Widget::with_drag_payload) widget containing the `point`.
+    pub fn drag_source_at(&self, point: Point) -> Option<&Widget> {
+        if !self.bounds.contains(point) {
+            return None;
+        }
+        let point = self.content_point(point);
+        self.children
+            .iter()
+            .find_map(|child| child.drag_source_at(point))
+            .or(self.drag_payload.as_ref().map(|_| self))
+    }
+
+    /// Returns the deepest widget [recognizing gestures](Widget::with_gesture) containing the `point`.
+    pub fn gesture_target_at(&self, point: Point) -> Option<&Widget> {
+        if !self.bounds.contains(point) {
+            return None;
+        }
+        let point = self.content_point(point);
+        self.children
+            .iter()
+            .find_map(|child| child.gesture_target_at(point))
+            .or((!self.gestures.is_empty()).then_some(self))
+    }
+
+    /// Returns the deepest [text input](WidgetKind::TextInput) containing the `point`.
+    pub fn text_input_at(&self, point: Point) -> Option<&Widget> {
+        if !self.bounds.contains(point) {
+            return None;
+        }
+        let point = self.content_point(point);
+        self.children
+            .iter()
+            .find_map(|child| child.text_input_at(point))
+            .or(self.text_input().map(|_| self))
+    }
+
+    /// Returns the deepest [viewport](WidgetKind::Viewport) containing the `point`.
+    pub fn viewport_at(&self, point: Point) -> Option<&Widget> {
+        if !self.bounds.contains(point) {
+            return None;
+        }
+        let point = self.content_point(point);
+        self.children
+            .iter()
+            .find_map(|child| child.viewport_at(point))
+            .or(self.viewport().map(|_| self))
+    }
+
+    /// Finds the widget with the given `key` in this widget's subtree.
+    pub(crate) fn find_key_mut(&mut self, key: WidgetKey) -> Option<&mut Widget> {
+        if self.key == key {
+            return Some(self);
+        }
+        self.children
+            .iter_mut()
+            .find_map(|child| child.find_key_mut(key))
+    }
+
+    /// Converts a `point` in the widget's coordinates to the coordinates its children are laid out in,
+    /// which differ for [viewports](WidgetKind::Viewport).
+    fn content_point(&self, point: Point) -> Point {
+        match &self.kind {
+            WidgetKind::Viewport(viewport) => viewport.to_content(point),
+            _ => point,
+        }
+    }
+
+    /// Highlights the drop target with the given `key` in this widget's subtree, and removes
+    /// the highlight of the others. Widgets whose highlight changes are marked as dirty.
+    pub(crate) fn highlight_drop_target(&mut self, key: Option<WidgetKey>) {
+        let highlighted = key == Some(self.key);
+        if self.drop_highlighted != highlighted {
+            self.drop_highlighted = highlighted;
+            self.dirty = true;
+        }
+        for child in &mut self.children {
+            child.highlight_drop_target(key);
+        }
+    }
+
+    /// Gives the keyboard focus to the widget with the given `key` in this widget's subtree, and removes
+    /// it from the others. Widgets whose focus changes are marked as dirty.
+    pub(crate) fn focus(&mut self, key: Option<WidgetKey>) {
+        let focused = key == Some(self.key);
+        if self.focused != focused {
+            self.focused = focused;
+            self.dirty = true;
+        }
+        for child in &mut self.children {
+            child.focus(key);
+        }
+    }
+
+    /// Computes the size the widget would like to have, including padding.
+    pub fn preferred_size(&self) -> (f32, f32) {
+        let padding = self.layout.padding * 2.0;
+        let (width, height) = match &self.kind {
+            WidgetKind::Column | WidgetKind::Row => {
+                self.children_size(matches!(self.kind, WidgetKind::Row))
+            }
+            WidgetKind::Label(text) | WidgetKind::Button(text) => {
+                let font = default_font(self.font_size());
+                let (width, _) = font.measure_str(self.text(text), None);
+                (width, self.font_size() * 1.5)
+            }
+            WidgetKind::TextInput(input) => {
+                let font = default_font(self.font_size());
+                let (width, _) = font.measure_str(input.text(), None);
+                (width, self.font_size() * 1.5)
+            }
+            WidgetKind::Image(ImageContent::Raster(image)) => {
+                (image.width() as f32, image.height() as f32)
+            }
+            WidgetKind::Image(ImageContent::Svg(_))
+            | WidgetKind::Spacer
+            | WidgetKind::Viewport(_) => (0.0, 0.0),
+        };
+        (
+            self.layout.width.unwrap_or(width + padding),
+            self.layout.height.unwrap_or(height + padding),
+        )
+    }
+
+    /// Computes the size of the widget's children laid out in a row or a column, including spacing.
+    fn children_size(&self, is_row: bool) -> (f32, f32) {
+        let spacing = self.layout.spacing * self.children.len().saturating_sub(1) as f32;
+        let (main, cross) = self.children.iter().fold((spacing, 0.0f32), |acc, child| {
+            let (width, height) = child.preferred_size();
+            let (main, cross) = if is_row {
+                (width, height)
+            } else {
+                (height, width)
+            };
+            (acc.0 + main, acc.1.max(cross))
+        });
+        if is_row {
+            (main, cross)
+        } else {
+            (cross, main)
+        }
+    }
+
+    /// Assigns `bounds` to the widget and lays out its children inside them.
+    ///
+    /// The children of [viewports](WidgetKind::Viewport) are laid out in content coordinates instead,
+    /// at their preferred size from the origin.
+    pub fn layout(&mut self, bounds: Rect) {
+        self.bounds = bounds;
+        let (is_row, bounds) = match self.kind {
+            WidgetKind::Row => (true, bounds),
+            WidgetKind::Column => (false, bounds),
+            WidgetKind::Viewport(_) => {
+                let padding = self.layout.padding * 2.0;
+                let (width, height) = self.children_size(false);
+                (false, Rect::from_wh(width + padding, height + padding))
+            }
+            _ => return,
+        };
+        if let WidgetKind::Viewport(viewport) = &mut self.kind {
+            viewport.set_bounds(self.bounds);
+        }
+
+        let content = bounds.with_inset((self.layout.padding, self.layout.padding));
+        let (main_size, cross_size) = if is_row {
+            (content.width(), content.height())
+        } else {
+            (content.height(), content.width())
+        };
+        let main_of = |child: &Widget| {
+            let (width, height) = child.preferred_size();
+            if is_row {
+                width
+            } else {
+                height
+            }
+        };
+
+        let spacing = self.layout.spacing * self.children.len().saturating_sub(1) as f32;
+        let (fixed, total_flex) = self.children.iter().fold((spacing, 0.0), |acc, child| {
+            if child.layout.flex > 0.0 {
+                (acc.0, acc.1 + child.layout.flex)
+            } else {
+                (acc.0 + main_of(child), acc.1)
+            }
+        });
+        let remaining = (main_size - fixed).max(0.0);
+
+        let mut offset = 0.0;
+        for child in &mut self.children {
+            let main = if child.layout.flex > 0.0 {
+                remaining * child.layout.flex / total_flex
+            } else {
+                main_of(child)
+            };
+            let child_bounds = if is_row {
+                let height = child.layout.height.unwrap_or(cross_size);
+                Rect::from_xywh(content.left + offset, content.top, main, height)
+            } else {
+                let width = child.layout.width.unwrap_or(cross_size);
+                Rect::from_xywh(content.left, content.top + offset, width, main)
+            };
+            child.layout(child_bounds);
+            offset += main + self.layout.spacing;
+        }
+    }
+
+    /// Paints the widget and its children on the `canvas`, using the bounds
+    /// assigned during the last [layout](Widget::layout).
+    ///
+    /// Widgets outside of the canvas' clip are skipped.
+    pub fn paint(&self, canvas: &Canvas) {
+        // Borders are stroked along the bounds, so half of them lies outside.
+        if canvas.quick_reject(&self.bounds.with_outset((1.0, 1.0))) {
+            return;
+        }
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+
+        let corner_radius = self.style.corner_radius.unwrap_or(match self.kind {
+            WidgetKind::Button(_) => DEFAULT_CORNER_RADIUS,
+            _ => 0.0,
+        });
+        let background = match (self.style.background, &self.kind) {
+            (Some(background), _) => Some(background),
+            (None, WidgetKind::Button(_)) => Some(BUTTON_BACKGROUND),
+            (None, WidgetKind::TextInput(_)) => Some(Color::WHITE),
+            (None, _) => None,
+        };
+        if let Some(background) = background {
+            paint.set_color(background);
+            canvas.draw_round_rect(self.bounds, corner_radius, corner_radius, &paint);
+        }
+        let border = match (self.style.border, &self.kind) {
+            _ if self.focused => Some(FOCUS_BORDER),
+            (None, WidgetKind::TextInput(_)) => Some(TEXT_INPUT_BORDER),
+            (border, _) => border,
+        };
+        if let Some(border) = border {
+            paint.set_style(PaintStyle::Stroke);
+            paint.set_stroke_width(if self.focused { 2.0 } else { 0.0 });
+            paint.set_color(border);
+            canvas.draw_round_rect(self.bounds, corner_radius, corner_radius, &paint);
+            paint.set_style(PaintStyle::Fill);
+            paint.set_stroke_width(0.0);
+        }
+        if self.drop_highlighted {
+            paint.set_color(DROP_HIGHLIGHT.with_a(0x40));
+            canvas.draw_round_rect(self.bounds, corner_radius, corner_radius, &paint);
+            paint.set_style(PaintStyle::Stroke);
+            paint.set_stroke_width(2.0);
+            paint.set_color(DROP_HIGHLIGHT);
+            canvas.draw_round_rect(self.bounds, corner_radius, corner_radius, &paint);
+            paint.set_style(PaintStyle::Fill);
+            paint.set_stroke_width(0.0);
+        }
+
+        match &self.kind {
+            WidgetKind::Label(text) | WidgetKind::Button(text) => {
+                let text = self.text(text);
+                let font = default_font(self.font_size());
+                paint.set_color(self.style.foreground.unwrap_or(Color::BLACK));
+                let content = self
+                    .bounds
+                    .with_inset((self.layout.padding, self.layout.padding));
+                let (width, _) = font.measure_str(&text, Some(&paint));
+                let x = match self.kind {
+                    WidgetKind::Button(_) => content.center_x() - width / 2.0,
+                    _ => content.left,
+                };
+                let baseline = content.center_y() + self.font_size() / 2.0 - 2.0;
+                canvas.save();
+                canvas.clip_rect(self.bounds, None, true);
+                canvas.draw_str(&text, (x, baseline), &font, &paint);
+                canvas.restore();
+            }
+            WidgetKind::TextInput(input) => {
+                let font = default_font(self.font_size());
+                let content = self
+                    .bounds
+                    .with_inset((self.layout.padding, self.layout.padding));
+                let x_at = |position: usize| {
+                    content.left + font.measure_str(&input.text()[..position], None).0
+                };
+                let (top, bottom) = (
+                    content.center_y() - self.font_size() * 0.6,
+                    content.center_y() + self.font_size() * 0.6,
+                );
+                canvas.save();
+                canvas.clip_rect(self.bounds, None, true);
+                let selection = input.selection();
+                if self.focused && !selection.is_empty() {
+                    paint.set_color(SELECTION);
+                    let (left, right) = (x_at(selection.start), x_at(selection.end));
+                    canvas.draw_rect(Rect::from_ltrb(left, top, right, bottom), &paint);
+                }
+                paint.set_color(self.style.foreground.unwrap_or(Color::BLACK));
+                let baseline = content.center_y() + self.font_size() / 2.0 - 2.0;
+                canvas.draw_str(input.text(), (content.left, baseline), &font, &paint);
+                if self.focused {
+                    let x = x_at(input.cursor());
+                    canvas.draw_rect(Rect::from_ltrb(x, top, x + 1.0, bottom), &paint);
+                }
+                canvas.restore();
+            }
+            WidgetKind::Image(content) => {
+                let dst = self
+                    .bounds
+                    .with_inset((self.layout.padding, self.layout.padding));
+                match content {
+                    ImageContent::Raster(image) => {
+                        // Mipmaps keep downscaled images smooth, and cubic resampling keeps
+                        // upscaled ones sharp on high density monitors.
+                        let sampling = match image.width() as f32 > dst.width() {
+                            true => SamplingOptions::new(FilterMode::Linear, MipmapMode::Linear),
+                            false => CubicResampler::mitchell().into(),
+                        };
+                        canvas.draw_image_rect_with_sampling_options(
+                            image, None, dst, sampling, &paint,
+                        );
+                    }
+                    ImageContent::Svg(dom) => {
+                        let mut dom = dom.clone();
+                        dom.set_container_size((dst.width(), dst.height()));
+                        canvas.save();
+                        canvas.clip_rect(dst, None, true);
+                        canvas.translate((dst.left, dst.top));
+                        dom.render(canvas);
+                        canvas.restore();
+                    }
+                }
+            }
+            WidgetKind::Viewport(viewport) => {
+                canvas.save();
+                canvas.clip_rect(self.bounds, None, true);
+                canvas.concat(&viewport.matrix());
+                for child in &self.children {
+                    child.paint(canvas);
+                }
+                canvas.restore();
+                return;
+            }
+            WidgetKind::Column | WidgetKind::Row | WidgetKind::Spacer => {}
+        }
+
+        for child in &self.children {
+            child.paint(canvas);
+        }
+    }
+
+    /// Returns the value of the widget's [text binding](Widget::with_text_binding),
+    /// or the `static_text` if it is not bound.
+    fn text<'a>(&self, static_text: &'a str) -> Cow<'a, str> {
+        match &self.text_binding {
+            Some(signal) => Cow::Owned(track_widget(self.key, || signal.get())),
+            None => Cow::Borrowed(static_text),
+        }
+    }
+
+    fn font_size(&self) -> f32 {
+        self.style.font_size.unwrap_or(DEFAULT_FONT_SIZE)
+    }
+}
+
+impl ResizeEdge {
+    /// Returns the edge or corner of the `bounds` that the `point` is within `border` of, if any.
+    pub fn at(bounds: Rect, point: Point, border: f32) -> Option<ResizeEdge> {
+        if !bounds.contains(point) {
+            return None;
+        }
+        let left = point.x < bounds.left + border;
+        let right = point.x >= bounds.right - border;
+        let top = point.y < bounds.top + border;
+        let bottom = point.y >= bounds.bottom - border;
+        match (top, bottom, left, right) {
+            (true, _, true, _) => Some(ResizeEdge::TopLeft),
+            (true, _, _, true) => Some(ResizeEdge::TopRight),
+            (_, true, true, _) => Some(ResizeEdge::BottomLeft),
+            (_, true, _, true) => Some(ResizeEdge::BottomRight),
+            (true, ..) => Some(ResizeEdge::Top),
+            (_, true, ..) => Some(ResizeEdge::Bottom),
+            (.., true, _) => Some(ResizeEdge::Left),
+            (.., true) => Some(ResizeEdge::Right),
+            _ => None,
+        }
+    }
+}
+
+impl Style {
+    /// Returns a style with the properties of `self`, falling back to the ones of `base`.
+    pub fn or(self, base: Style) -> Style {
+        Style {
+            background: self.background.or(base.background),
+            foreground: self.foreground.or(base.foreground),
+            border: self.border.or(base.border),
+            corner_radius: self.corner_radius.or(base.corner_radius),
+            font_size: self.font_size.or(base.font_size),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sized(width: f32, height: f32) -> Layout {
+        Layout {
+            width: Some(width),
+            height: Some(height),
+            ..Layout::default()
+        }
+    }
+
+    #[test]
+    fn resize_edges_are_found_within_the_border() {
+        let bounds = Rect::from_wh(100.0, 80.0);
+        let edge = |x, y| ResizeEdge::at(bounds, Point::new(x, y), 6.0);
+
+        assert_eq!(edge(2.0, 2.0), Some(ResizeEdge::TopLeft));
+        assert_eq!(edge(98.0, 2.0), Some(ResizeEdge::TopRight));
+        assert_eq!(edge(2.0, 78.0), Some(ResizeEdge::BottomLeft));
+        assert_eq!(edge(98.0, 78.0), Some(ResizeEdge::BottomRight));
+        assert_eq!(edge(50.0, 2.0), Some(ResizeEdge::Top));
+        assert_eq!(edge(50.0, 78.0), Some(ResizeEdge::Bottom));
+        assert_eq!(edge(2.0, 40.0), Some(ResizeEdge::Left));
+        assert_eq!(edge(98.0, 40.0), Some(ResizeEdge::Right));
+        assert_eq!(edge(50.0, 40.0), None);
+        assert_eq!(edge(150.0, 40.0), None);
+    }
+
+    #[test]
+    fn regions_are_the_ones_of_the_deepest_widgets() {
+        let title_bar = Widget::new(WidgetKind::Row)
+            .with_layout(Layout {
+                height: Some(30.0),
+                ..Layout::default()
+            })
+            .with_hit_region(HitRegion::Caption)
+            .with_child(
+                Widget::new(WidgetKind::Spacer)
+                    .with_layout(sized(40.0, 30.0))
+                    .with_action("close"),
+            );
+        let overlay = Widget::new(WidgetKind::Spacer)
+            .with_layout(Layout {
+                flex: 1.0,
+                ..Layout::default()
+            })
+            .with_hit_region(HitRegion::PassThrough);
+        let mut root = Widget::new(WidgetKind::Column)
+            .with_child(title_bar)
+            .with_child(overlay);
+        root.layout(Rect::from_wh(200.0, 100.0));
+
+        // Widgets with an action are always clickable.
+        assert_eq!(
+            root.region_at(Point::new(20.0, 15.0)),
+            Some(HitRegion::Client)
+        );
+        assert_eq!(
+            root.region_at(Point::new(100.0, 15.0)),
+            Some(HitRegion::Caption)
+        );
+        assert_eq!(
+            root.region_at(Point::new(100.0, 60.0)),
+            Some(HitRegion::PassThrough)
+        );
+        assert_eq!(root.region_at(Point::new(300.0, 15.0)), None);
+    }
+
+    #[test]
+    fn window_bounds_are_mapped_through_viewports() {
+        let child = Widget::new(WidgetKind::Spacer).with_layout(sized(50.0, 50.0));
+        let key = child.key();
+        let mut root = Widget::new(WidgetKind::Viewport(Viewport::new())).with_child(child);
+        root.layout(Rect::from_xywh(10.0, 20.0, 200.0, 100.0));
+        root.update_viewport(|viewport| viewport.zoom_by(2.0, Point::new(10.0, 20.0)));
+
+        assert_eq!(root.children()[0].bounds(), Rect::from_wh(50.0, 50.0));
+        assert_eq!(
+            root.window_bounds(key),
+            Some(Rect::from_xywh(10.0, 20.0, 100.0, 100.0))
+        );
+        assert_eq!(root.window_bounds(root.key()), Some(root.bounds()));
+    }
+}
@@ -0,0 +1,34 @@
+mod common;
+
+use common::example_harness;
+
+#[test]
+fn copy_button_writes_to_the_in_memory_clipboard() {
+    let mut harness = example_harness();
+    assert_eq!(harness.clipboard().text(), None);
+
+    assert!(harness.click_widget("increment"));
+    assert!(harness.click_widget("copy"));
+
+    assert_eq!(harness.take_actions(), ["increment", "copy_clicks"]);
+    assert_eq!(
+        harness.clipboard().text().as_deref(),
+        Some("Clicked 1 times")
+    );
+}
+
+#[test]
+fn windows_share_the_harness_clipboard() {
+    let harness = example_harness();
+    let clipboard = harness.window().unwrap().clipboard();
+
+    clipboard.set_html("<b>bold</b>", "bold");
+
+    assert_eq!(harness.clipboard().html().as_deref(), Some("<b>bold</b>"));
+    assert_eq!(harness.clipboard().text().as_deref(), Some("bold"));
+    harness.clipboard().set_text("pasted");
+    assert_eq!(
+        harness.window().unwrap().clipboard().text().as_deref(),
+        Some("pasted")
+    );
+}
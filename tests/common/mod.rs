@@ -0,0 +1,11 @@
+use rust_gui_template::core::Harness;
+use winit::dpi::PhysicalSize;
+
+const EXAMPLE_UI_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/ui/example.ron");
+
+/// Creates a harness with an 800x600 window showing the example UI.
+pub fn example_harness() -> Harness {
+    let mut harness = Harness::new(PhysicalSize::new(800, 600));
+    harness.load_ui(EXAMPLE_UI_PATH).unwrap();
+    harness
+}
@@ -0,0 +1,68 @@
+use rust_gui_template::core::{Dialog, DialogHandle, DialogResponse, Harness};
+use std::cell::RefCell;
+use std::rc::Rc;
+use winit::dpi::PhysicalSize;
+use winit::keyboard::{Key, NamedKey};
+
+/// Opens the `dialog` on top of the harness' window, and selects the dialog's window.
+fn open_dialog(harness: &mut Harness, dialog: Dialog) -> DialogHandle {
+    let handle = harness.window_mut().unwrap().open_dialog(dialog);
+    harness.update();
+    let dialog_id = *harness.windows().last().unwrap();
+    assert!(harness.select_window(dialog_id));
+    handle
+}
+
+/// Awaits the dialog's `handle` in a task spawned on the harness, and returns where its response is stored.
+fn await_response(harness: &Harness, handle: DialogHandle) -> Rc<RefCell<Option<DialogResponse>>> {
+    let response = Rc::new(RefCell::new(None));
+    let task_response = response.clone();
+    harness.spawner().spawn(async move {
+        *task_response.borrow_mut() = Some(handle.await);
+    });
+    response
+}
+
+#[test]
+fn accepted_alerts_resolve_to_ok() {
+    let mut harness = Harness::new(PhysicalSize::new(800, 600));
+    let parent_id = harness.window_id();
+    let handle = open_dialog(
+        &mut harness,
+        Dialog::alert("Saved", "The file has been saved."),
+    );
+    let response = await_response(&harness, handle);
+    assert_eq!(harness.windows().len(), 2);
+
+    harness.press_key(Key::Named(NamedKey::Enter));
+
+    assert_eq!(*response.borrow(), Some(DialogResponse::Ok));
+    assert_eq!(harness.windows(), [parent_id]);
+}
+
+#[test]
+fn accepted_prompts_resolve_to_the_typed_text() {
+    let mut harness = Harness::new(PhysicalSize::new(800, 600));
+    let dialog = Dialog::prompt("Rename", "Enter a new name:", "old");
+    let handle = open_dialog(&mut harness, dialog);
+    let response = await_response(&harness, handle);
+
+    for _ in 0..3 {
+        harness.press_key(Key::Named(NamedKey::Backspace));
+    }
+    harness.type_text("new");
+    harness.press_key(Key::Named(NamedKey::Enter));
+
+    assert_eq!(*response.borrow(), Some(DialogResponse::Text("new".into())));
+}
+
+#[test]
+fn closed_dialogs_resolve_to_cancel() {
+    let mut harness = Harness::new(PhysicalSize::new(800, 600));
+    let dialog = Dialog::confirm("Close window", "Do you want to close this window?");
+    let handle = open_dialog(&mut harness, dialog);
+
+    harness.close_window();
+
+    assert_eq!(handle.try_response(), Some(DialogResponse::Cancel));
+}
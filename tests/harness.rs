@@ -0,0 +1,77 @@
+mod common;
+
+use common::example_harness;
+use rust_gui_template::core::{Input, RecordedEvent, Recording};
+use std::time::Duration;
+use winit::dpi::LogicalPosition;
+use winit::event::{ElementState, MouseButton};
+
+#[test]
+fn clicking_a_button_handles_its_action() {
+    let mut harness = example_harness();
+    let clicks = harness.store().signal("clicks");
+
+    assert!(harness.click_widget("increment"));
+
+    assert_eq!(harness.take_actions(), ["increment"]);
+    assert_eq!(clicks.get(), "Clicked 1 times");
+    assert!(harness.take_actions().is_empty());
+}
+
+#[test]
+fn clicking_a_missing_widget_does_nothing() {
+    let mut harness = example_harness();
+
+    assert!(!harness.click_widget("missing"));
+    assert!(harness.take_actions().is_empty());
+}
+
+#[test]
+fn debounced_callbacks_run_once_the_time_has_advanced() {
+    let mut harness = example_harness();
+    let clicks = harness.store().signal("clicks");
+    harness.click_widget("increment");
+    harness.click_widget("increment");
+
+    harness.advance(Duration::from_millis(900));
+    assert_eq!(clicks.get(), "Clicked 2 times");
+    harness.advance(Duration::from_millis(200));
+    assert_eq!(clicks.get(), "Clicked 2 times in a row");
+}
+
+#[test]
+fn replayed_clicks_handle_actions() {
+    let mut harness = example_harness();
+    let center = harness
+        .ui()
+        .and_then(|root| root.window_bounds(root.find("increment")?.key()))
+        .unwrap()
+        .center();
+    let inputs = [
+        Input::CursorMoved(LogicalPosition::new(center.x, center.y)),
+        Input::MouseButton {
+            button: MouseButton::Left,
+            state: ElementState::Pressed,
+        },
+        Input::MouseButton {
+            button: MouseButton::Left,
+            state: ElementState::Released,
+        },
+    ];
+    let source: String = inputs
+        .into_iter()
+        .enumerate()
+        .map(|(i, input)| {
+            let event = RecordedEvent {
+                time: Duration::from_millis(100 * i as u64),
+                window: 0,
+                input,
+            };
+            ron::to_string(&event).unwrap() + "\n"
+        })
+        .collect();
+
+    harness.replay(&Recording::parse(&source).unwrap());
+
+    assert_eq!(harness.take_actions(), ["increment"]);
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use common::example_harness;
+use rust_gui_template::core::Harness;
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+
+/// Presses Ctrl with the character `key`.
+fn press_shortcut(harness: &mut Harness, key: &str) {
+    harness.set_modifiers(ModifiersState::CONTROL);
+    harness.press_key(Key::Character(key.into()));
+    harness.set_modifiers(ModifiersState::empty());
+}
+
+#[test]
+fn typed_text_is_written_to_the_bound_signal() {
+    let mut harness = example_harness();
+    let name = harness.store().signal("name");
+
+    assert!(harness.click_widget("name"));
+    harness.type_text("Ferris");
+    harness.press_key(Key::Named(NamedKey::Backspace));
+
+    assert_eq!(name.get(), "Ferri");
+    assert!(harness.window().unwrap().is_editing_text());
+}
+
+#[test]
+fn typed_text_does_not_trigger_shortcuts() {
+    let mut harness = example_harness();
+    harness.click_widget("name");
+
+    harness.type_text("qa");
+
+    assert_eq!(harness.windows().len(), 1);
+    harness.press_key(Key::Named(NamedKey::Escape));
+    assert!(!harness.window().unwrap().is_editing_text());
+}
+
+#[test]
+fn selected_text_is_copied_cut_and_pasted() {
+    let mut harness = example_harness();
+    let name = harness.store().signal("name");
+    harness.click_widget("name");
+    harness.type_text("Hello");
+
+    press_shortcut(&mut harness, "a");
+    press_shortcut(&mut harness, "c");
+    assert_eq!(harness.clipboard().text().as_deref(), Some("Hello"));
+    assert_eq!(name.get(), "Hello");
+
+    press_shortcut(&mut harness, "x");
+    assert_eq!(name.get(), "");
+
+    harness.clipboard().set_text("Hi\nthere");
+    press_shortcut(&mut harness, "v");
+    assert_eq!(name.get(), "Hithere");
+}